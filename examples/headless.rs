@@ -0,0 +1,68 @@
+//! Play a game without any UI, picking random actions for the player
+//!
+//! Run with: `cargo run --example headless -- [seed]`
+
+extern crate hex2d;
+extern crate rand;
+extern crate rhex;
+
+use std::env;
+
+use hex2d::Angle;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+use rhex::game::{Action, Engine};
+use rhex::game::actor::Race;
+use rhex::game::error::Error;
+
+const MAX_TURNS: u64 = 1000;
+
+fn random_action<R: Rng>(rng: &mut R) -> Action {
+    match rng.gen_range(0..6) {
+        0 => Action::Turn(Angle::Left),
+        1 => Action::Turn(Angle::Right),
+        2 => Action::Pick(0),
+        3 => Action::Descend,
+        _ => Action::Move(Angle::Forward),
+    }
+}
+
+fn main() {
+    let seed = env::args().nth(1).and_then(|s| s.parse().ok()).unwrap_or(0);
+
+    let mut engine = Engine::new(seed);
+    engine.initial_spawn(Race::Human);
+
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    while engine.turn() < MAX_TURNS {
+        match engine.advance_until_player_input() {
+            Ok(_) => {}
+            Err(Error::PlayerDead) | Err(Error::GameWon) => break,
+            Err(err) => panic!("engine failed: {}", err),
+        }
+
+        if engine.player().is_dead() {
+            break;
+        }
+
+        // Random actions are often impossible (eg. no stairs to descend);
+        // just pick another one
+        match engine.player_act(random_action(&mut rng)) {
+            Ok(()) | Err(Error::InvalidAction(_)) => {}
+            Err(err) => panic!("engine failed: {}", err),
+        }
+    }
+
+    let player = engine.player();
+    let record = engine.record();
+    println!("seed: {}, turn: {}, level: {}, hp: {}/{}, kills: {}, score: {}",
+             seed,
+             engine.turn(),
+             engine.current_location().level,
+             player.hp,
+             player.stats.base.max_hp,
+             record.kills,
+             record.score());
+}
@@ -1,4 +1,3 @@
-use rand;
 use rand::Rng;
 
 use hex2dext::algo::bfs;
@@ -8,21 +7,23 @@ use hex2d::Angle::{Left, Right, Forward, Back, LeftBack};
 use game;
 use game::actor;
 
+/// Decision making of monsters
 pub trait Ai {
-    fn action(&mut self, id: actor::Id, engine: &game::Engine) -> game::Action;
+    /// Action for the actor with a given `id` in its tick
+    fn action(&mut self, id: actor::Id, loc: &game::Location, rng: &mut game::Rng) -> game::Action;
 }
 
+/// Chase the player, collect items and follow noises
 pub struct Simple;
 
 impl Ai for Simple {
-    fn action(&mut self, id: actor::Id, engine: &game::Engine) -> game::Action {
-        grue(&engine.current_location().actors_byid[&id],
-             engine.current_location())
+    fn action(&mut self, id: actor::Id, loc: &game::Location, rng: &mut game::Rng) -> game::Action {
+        grue(&loc.actors_byid[&id], loc, rng)
     }
 }
 
-fn roam() -> game::Action {
-    match rand::thread_rng().gen_range(0..10) {
+fn roam(rng: &mut game::Rng) -> game::Action {
+    match rng.gen_range(0..10) {
         0 => game::Action::Turn(Right),
         1 => game::Action::Turn(Left),
         2 => game::Action::Move(Forward),
@@ -48,16 +49,16 @@ fn closest_reachable<F>(gstate: &game::Location,
     bfs.find().map(|pos| (pos, bfs.backtrace_last(pos).unwrap()))
 }
 
-fn grue(astate: &actor::Actor, gstate: &game::Location) -> game::Action {
+fn grue(astate: &actor::Actor, gstate: &game::Location, rng: &mut game::Rng) -> game::Action {
 
     for &visible_pos in &astate.visible {
         if gstate.at(visible_pos).actor_map_or(false, |a| a.is_player()) {
-            return go_to(visible_pos, astate, gstate);
+            return go_to(visible_pos, astate, gstate, rng);
         }
     }
 
     if gstate.at(astate.head()).item_map_or(false, |_| true) {
-        return game::Action::Pick;
+        return game::Action::Pick(0);
     }
 
     if gstate.at(astate.pos.coord).item_map_or(false, |_| true) {
@@ -66,23 +67,27 @@ fn grue(astate: &actor::Actor, gstate: &game::Location) -> game::Action {
 
     for &visible_coord in &astate.visible {
         if gstate.at(visible_coord).item_map_or(false, |_| true) {
-            return go_to(visible_coord, astate, gstate);
+            return go_to(visible_coord, astate, gstate, rng);
         }
     }
 
     for (&coord, _) in &astate.heard {
         if astate.pos.coord != coord {
-            return go_to(coord, astate, gstate);
+            return go_to(coord, astate, gstate, rng);
         }
     }
 
-    match rand::thread_rng().gen_range(0..5) {
-        0 => roam(),
+    match rng.gen_range(0..5) {
+        0 => roam(rng),
         _ => game::Action::Wait,
     }
 }
 
-fn go_to(c: Coordinate, astate: &actor::Actor, gstate: &game::Location) -> game::Action {
+fn go_to(c: Coordinate,
+         astate: &actor::Actor,
+         gstate: &game::Location,
+         rng: &mut game::Rng)
+         -> game::Action {
     let ndir = match astate.pos.coord.direction_to_cw(c) {
         None => return game::Action::Wait,
         Some(dir) => dir,
@@ -112,13 +117,16 @@ fn go_to(c: Coordinate, astate: &actor::Actor, gstate: &game::Location) -> game:
     let reachable = closest_reachable(gstate, astate.pos.coord, 10, |pos| pos == c);
 
     if let Some((_, n)) = reachable {
-        go_to(n, astate, gstate)
+        go_to(n, astate, gstate, rng)
     } else {
-        roam()
+        roam(rng)
     }
 }
 
-fn _pony_follow(astate: &actor::Actor, gstate: &game::Location) -> game::Action {
+fn _pony_follow(astate: &actor::Actor,
+                gstate: &game::Location,
+                rng: &mut game::Rng)
+                -> game::Action {
     let start = astate.pos.coord;
 
     let player_pos = closest_reachable(gstate,
@@ -143,8 +151,8 @@ fn _pony_follow(astate: &actor::Actor, gstate: &game::Location) -> game::Action
     };
 
     if let Some((_, neigh)) = player_pos {
-        go_to(neigh, astate, gstate)
+        go_to(neigh, astate, gstate, rng)
     } else {
-        roam()
+        roam(rng)
     }
 }
@@ -2,11 +2,21 @@ use hex2d::IntegerSpacing;
 
 pub static SPACING: IntegerSpacing<i32> = IntegerSpacing::PointyTop(2, 1);
 
+pub const SAVE_FILE: &'static str = "rhex.sav";
+/// Where a save that failed to load is moved
+pub const BAD_SAVE_FILE: &'static str = "rhex.sav.bad";
+pub const REPLAY_FILE: &'static str = "rhex.replay";
+pub const HIGH_SCORES_FILE: &'static str = "rhex.scores";
+
 pub const WALL_CH: char = '#';
 pub const DOOR_OPEN_CH: char = '_';
 pub const DOOR_CLOSED_CH: char = '×';
 pub const STATUE_CH: char = '&';
+pub const PILE_CH: char = '=';
+/// Items listed at once when picking from a pile
+pub const PILE_PAGE: usize = 26;
 pub const STAIRS_DOWN_CH: char = '>';
+pub const STAIRS_UP_CH: char = '<';
 pub const WATER_CH: char = '~';
 pub const UNICODE_DOT: char = '\u{2022}';
 
@@ -18,6 +28,7 @@ pub const KEY_LOWB: i32 = 'b' as i32;
 pub const KEY_LOWC: i32 = 'c' as i32;
 pub const KEY_LOWD: i32 = 'd' as i32;
 pub const KEY_LOWY: i32 = 'y' as i32;
+pub const KEY_LOWN: i32 = 'n' as i32;
 pub const KEY_LOWH: i32 = 'h' as i32;
 pub const KEY_LOWL: i32 = 'l' as i32;
 pub const KEY_LOWE: i32 = 'e' as i32;
@@ -30,7 +41,10 @@ pub const KEY_LOWQ: i32 = 'q' as i32;
 pub const KEY_LOWJ: i32 = 'j' as i32;
 pub const KEY_LOWF: i32 = 'f' as i32;
 pub const KEY_LOWS: i32 = 's' as i32;
+pub const KEY_LOWT: i32 = 't' as i32;
+pub const KEY_LOWP: i32 = 'p' as i32;
 pub const KEY_CAPY: i32 = 'Y' as i32;
+pub const KEY_CAPN: i32 = 'N' as i32;
 pub const KEY_CAPH: i32 = 'H' as i32;
 pub const KEY_CAPL: i32 = 'L' as i32;
 pub const KEY_CAPE: i32 = 'E' as i32;
@@ -39,8 +53,14 @@ pub const KEY_CAPI: i32 = 'I' as i32;
 pub const KEY_CAPK: i32 = 'K' as i32;
 pub const KEY_CAPW: i32 = 'W' as i32;
 pub const KEY_CAPJ: i32 = 'J' as i32;
+pub const KEY_CAPA: i32 = 'A' as i32;
 pub const KEY_DOT: i32 = '.' as i32;
+pub const KEY_SPACE: i32 = ' ' as i32;
+pub const KEY_PLUS: i32 = '+' as i32;
+pub const KEY_MINUS: i32 = '-' as i32;
 pub const KEY_COMMA: i32 = ',' as i32;
 pub const KEY_HELP: i32 = '?' as i32;
 pub const KEY_GOTO: i32 = 'G' as i32;
 pub const KEY_DESCEND: i32 = '>' as i32;
+pub const KEY_ASCEND: i32 = '<' as i32;
+pub const KEY_CAST: i32 = 'z' as i32;
@@ -296,6 +296,7 @@ impl MapRenderer {
                         }
                         Some(tile::Statue) => glyph = STATUE_CH,
                         Some(tile::Stairs) => glyph = STAIRS_DOWN_CH,
+                        Some(tile::StairsUp) => glyph = STAIRS_UP_CH,
                     }
 
                     if visible && cur_loc.at(c).tile().light > 0 {
@@ -352,7 +353,7 @@ impl MapRenderer {
         }
 
         if cur_loc.at(c).item().is_some() {
-            let item = cur_loc.at(c).item().unwrap();
+            let items = cur_loc.at(c).items();
 
             let color = color::WALL_FG;
             let fg = color_by_visibility(color, base.visible, base.light);
@@ -361,7 +362,7 @@ impl MapRenderer {
                 fg: fg,
                 bg: base.base.bg,
                 bold: player.discovered.contains(&c),
-                glyph: item_to_char(item.category())
+                glyph: pile_to_char(items)
             });
         }
     }
@@ -653,6 +654,16 @@ pub fn item_to_char(t: item::Category) -> char {
         item::Category::Armor => '[',
         item::Category::Misc => '"',
         item::Category::Consumable => '%',
+        item::Category::Quest => '*',
+        item::Category::Ammo => '(',
+    }
+}
+
+/// The glyph of the only item, or one marking a pile
+pub fn pile_to_char(items: &[Box<item::Item>]) -> char {
+    match items {
+        [item] => item_to_char(item.category()),
+        _ => PILE_CH,
     }
 }
 
@@ -13,6 +13,10 @@ mod map;
 mod error;
 pub use self::error::*;
 
+mod replay;
+
+mod morgue;
+
 
 mod ui;
 pub use self::ui::*;
@@ -64,6 +68,7 @@ pub enum AutoMoveAction {
 #[derive(Copy, Clone, PartialEq, Eq)]
 pub enum GoToType {
     Stairs,
+    StairsUp,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq)]
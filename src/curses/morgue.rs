@@ -0,0 +1,144 @@
+use std::cmp;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use hex2d::Coordinate;
+
+use game::{Actor, Engine, Location, tile};
+use game::actor::Slot;
+use game::skill::Skill;
+
+use super::consts::*;
+use super::map::{pile_to_char, race_to_char};
+use super::LogEntry;
+
+/// How many of the last log messages go into the morgue file
+const LOG_LINES: usize = 20;
+
+/// Write a plain text summary of a finished game
+pub fn write<P: AsRef<Path>>(path: P, engine: &Engine, log: &VecDeque<LogEntry>) -> io::Result<()> {
+    let mut f = BufWriter::new(File::create(path)?);
+
+    let player = engine.player();
+    let record = engine.record();
+    let knowledge = engine.knowledge();
+    let loc = engine.current_location();
+
+    writeln!(f, "rhex morgue file, seed {}\n", engine.seed())?;
+    writeln!(f,
+             "The {} {} on level {} in turn {}.",
+             player.description(),
+             record.end_description(),
+             loc.level,
+             engine.turn())?;
+    writeln!(f, "Killed {} monsters. Score: {}.", record.kills, record.score())?;
+    if !record.artifacts.is_empty() {
+        let names: Vec<_> = record.artifacts.iter().map(|a| a.name()).collect();
+        writeln!(f, "Found {}.", names.join(", "))?;
+    }
+    writeln!(f)?;
+
+    let stats = &player.stats;
+    writeln!(f, "Str: {:>2}  DMG: {:>2}  ACC: {:>2}", stats.base.str_, stats.melee_dmg, stats.melee_acc)?;
+    writeln!(f, "Int: {:>2}   AC: {:>2}   EV: {:>2}", stats.base.int, stats.base.ac, stats.base.ev)?;
+    writeln!(f, "Dex: {:>2}  SPD: {:>2}   XL: {:>2}", stats.base.dex, stats.base.speed, player.xp_level)?;
+    writeln!(f, "HP: {}/{}  MP: {}/{}  SP: {}/{}\n",
+             player.hp, stats.base.max_hp,
+             player.mp, stats.base.max_mp,
+             player.sp, stats.base.max_sp)?;
+
+    writeln!(f, "Skills:")?;
+    for skill in Skill::all().iter() {
+        writeln!(f, " {:<8} {}", skill.description(), player.skills.level(*skill))?;
+    }
+
+    writeln!(f, "\nEquipped:")?;
+    for &slot in &[Slot::RHand, Slot::LHand, Slot::Head, Slot::Body, Slot::Feet, Slot::Cloak, Slot::Quick] {
+        if let Some(&(ch, ref item)) = player.items_equipped.get(&slot) {
+            writeln!(f, " {} - {} [{:?}]", ch, item.known(knowledge), slot)?;
+        }
+    }
+
+    writeln!(f, "\nInventory:")?;
+    let mut backpack: Vec<_> = player.items_backpack.iter().collect();
+    backpack.sort_by_key(|&(ch, _)| *ch);
+    for (ch, item) in backpack {
+        writeln!(f, " {} - {}", ch, item.known(knowledge))?;
+    }
+
+    writeln!(f, "\nLast messages:")?;
+    for entry in log.iter().take(LOG_LINES).collect::<Vec<_>>().iter().rev() {
+        writeln!(f, " {:>5}: {}", entry.turn, entry.text)?;
+    }
+
+    writeln!(f, "\nMap:")?;
+    for line in map_lines(loc, player) {
+        writeln!(f, "{}", line)?;
+    }
+
+    Ok(())
+}
+
+fn glyph(loc: &Location, player: &Actor, coord: Coordinate) -> char {
+    let at = loc.at(coord);
+
+    if let Some(race) = at.actor_map_or(None, |a| Some(a.race)) {
+        if player.sees(coord) || coord == player.coord() {
+            return race_to_char(race);
+        }
+    }
+
+    if at.item().is_some() {
+        return pile_to_char(at.items());
+    }
+
+    let tile = at.tile();
+    match tile.feature {
+        Some(tile::Door(true)) => DOOR_OPEN_CH,
+        Some(tile::Door(false)) => DOOR_CLOSED_CH,
+        Some(tile::Statue) => STATUE_CH,
+        Some(tile::Stairs) => STAIRS_DOWN_CH,
+        Some(tile::StairsUp) => STAIRS_UP_CH,
+        None => {
+            match tile.type_ {
+                tile::Wall => WALL_CH,
+                tile::Water => WATER_CH,
+                tile::Empty => '.',
+            }
+        }
+    }
+}
+
+/// Known part of the level, as lines of text
+fn map_lines(loc: &Location, player: &Actor) -> Vec<String> {
+    let mut chars = vec![];
+    let (mut min_x, mut min_y) = (i32::max_value(), i32::max_value());
+    let (mut max_x, mut max_y) = (i32::min_value(), i32::min_value());
+
+    for &coord in &player.known {
+        let (x, y) = coord.to_pixel_integer(SPACING);
+        min_x = cmp::min(min_x, x);
+        min_y = cmp::min(min_y, y);
+        max_x = cmp::max(max_x, x);
+        max_y = cmp::max(max_y, y);
+        chars.push((x, y, glyph(loc, player, coord)));
+    }
+
+    if chars.is_empty() {
+        return vec![];
+    }
+
+    let width = (max_x - min_x + 1) as usize;
+    let height = (max_y - min_y + 1) as usize;
+    let mut lines = vec![vec![' '; width]; height];
+
+    for (x, y, ch) in chars {
+        lines[(y - min_y) as usize][(x - min_x) as usize] = ch;
+    }
+
+    lines.into_iter()
+         .map(|line| line.into_iter().collect::<String>().trim_end().to_owned())
+         .collect()
+}
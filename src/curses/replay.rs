@@ -0,0 +1,83 @@
+use std::cmp;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use game::journal::{Entry, Journal};
+
+const DELAY_MIN_MS: u64 = 10;
+const DELAY_MAX_MS: u64 = 2000;
+
+/// State of a game being replayed from a `Journal`
+pub struct Replay {
+    entries: VecDeque<Entry>,
+    delay_ms: u64,
+    paused: bool,
+    step: bool,
+    fast_forward: bool,
+}
+
+impl Replay {
+    pub fn new(journal: Journal, delay_ms: u64) -> Self {
+        Replay {
+            entries: journal.entries.into_iter().collect(),
+            delay_ms: cmp::min(cmp::max(delay_ms, DELAY_MIN_MS), DELAY_MAX_MS),
+            paused: false,
+            step: false,
+            fast_forward: false,
+        }
+    }
+
+    /// Is it allowed to feed the next input to the engine?
+    pub fn can_advance(&self) -> bool {
+        !self.paused || self.step
+    }
+
+    pub fn peek(&self) -> Option<&Entry> {
+        self.entries.front()
+    }
+
+    pub fn pop(&mut self) -> Option<Entry> {
+        self.step = false;
+        self.entries.pop_front()
+    }
+
+    /// Drop the next entry without counting it as a step
+    pub fn discard(&mut self) {
+        self.entries.pop_front();
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    /// Advance one input while paused
+    pub fn step(&mut self) {
+        self.paused = true;
+        self.step = true;
+    }
+
+    pub fn toggle_fast_forward(&mut self) {
+        self.fast_forward = !self.fast_forward;
+    }
+
+    pub fn faster(&mut self) {
+        self.delay_ms = cmp::max(self.delay_ms / 2, DELAY_MIN_MS);
+    }
+
+    pub fn slower(&mut self) {
+        self.delay_ms = cmp::min(self.delay_ms * 2, DELAY_MAX_MS);
+    }
+
+    /// Time between two consecutive inputs
+    pub fn delay(&self) -> Duration {
+        if self.fast_forward {
+            Duration::from_millis(0)
+        } else {
+            Duration::from_millis(self.delay_ms)
+        }
+    }
+}
@@ -3,10 +3,12 @@ use std::collections::{VecDeque};
 use std::env;
 use std::{thread, cmp, fmt};
 use std::fmt::Write as FmtWrite;
+use std::mem;
 use std::process::Command;
 use std::time::{Instant, Duration};
 
 use num::integer::Integer;
+use chrono;
 
 use game::Action::*;
 
@@ -20,11 +22,20 @@ use super::{color, Window};
 use super::{LogEntry, AutoMoveType, AutoMoveAction, LogEvent, Event, GoToType};
 use super::{Error, Result};
 use super::map::MapRenderer;
+use super::replay::Replay;
+use super::morgue;
 
-use game::{actor, Location, Actor, area};
+use game::{Location, Actor, area};
+use game::item::Item;
 use game;
-use game::actor::{Race, Slot};
+use game::journal::{Entry, Input, Journal};
+use game::event::{Obstacle, SubscriberId};
+use game::score::{self, HighScores};
+use game::actor::{self, Arc, Attribute, Race, Slot};
 use game::tile;
+use game::skill::Skill;
+use game::spell::Spell;
+use game::status::Status;
 use util;
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
@@ -63,7 +74,7 @@ impl Windows {
         let (max_y, max_x) = Ui::screen_size();
 
         let mid_x = max_x - 30;
-        let mid_y = 12;
+        let mid_y = 15;
 
         let map_window = Window::new(mid_x, max_y, 0, 0);
         let stats_window = Window::new(max_x - mid_x, mid_y, mid_x, 0);
@@ -85,19 +96,28 @@ pub enum InvMode {
     View,
     Equip,
     Drop_,
+    Throw,
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub enum FSMode {
     Help,
     Intro,
+    Resume,
     PickRace,
     Quit,
+    GameOver,
+    HighScores,
+    /// Spending attribute points
+    LevelUp,
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub enum TargetMode {
     Ranged,
+    Spell(Spell),
+    /// Throw a backpack item with a given letter
+    Throw(char),
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
@@ -108,6 +128,9 @@ pub enum Mode {
     Target(TargetMode),
     FullScreen(FSMode),
     Inventory(InvMode),
+    Spells,
+    /// Choosing an item from the pile in front, on a given page
+    Pile(usize),
 }
 
 pub struct Ui {
@@ -128,8 +151,16 @@ pub struct Ui {
     input_mode : InputMode,
 
     engine: game::Engine,
+    engine_events: SubscriberId,
+    /// Events since the end of the previous turn
+    turn_events: Vec<game::Event>,
     exit: bool,
     spawned: bool,
+    game_over: bool,
+    /// Rank of the finished run in `high_scores`
+    game_over_rank: Option<usize>,
+    game_over_morgue: Option<String>,
+    high_scores: HighScores,
 
     automoving: Option<AutoMoveType>,
     automoving_stopped_turn: u64,
@@ -141,6 +172,8 @@ pub struct Ui {
     game_action_queue: VecDeque<game::Action>,
 
     map_renderer: MapRenderer,
+
+    replay: Option<Replay>,
 }
 
 use self::Action::*;
@@ -158,7 +191,7 @@ enum GlobalAction {
 }
 
 impl Ui {
-    pub fn new() -> Result<Self> {
+    pub fn new(seed: u64) -> Result<Self> {
         if Ui::get_terminal_colors() != 256 {
             return Err(Error::ColorCount);
         }
@@ -187,7 +220,8 @@ impl Ui {
         let green_color = nc::COLOR_PAIR(calloc.get(color::GREEN_FG, color::BACKGROUND_BG));
         let red_color = nc::COLOR_PAIR(calloc.get(color::RED_FG, color::BACKGROUND_BG));
 
-        let engine = game::Engine::new();
+        let mut engine = game::Engine::new(seed);
+        let engine_events = engine.subscribe();
 
 
         let cpair = nc::COLOR_PAIR(calloc.get(color::VISIBLE_FG, color::BACKGROUND_BG));
@@ -211,8 +245,14 @@ impl Ui {
 
             exit: false,
             spawned: false,
+            game_over: false,
+            game_over_rank: None,
+            game_over_morgue: None,
+            high_scores: HighScores::load(HIGH_SCORES_FILE).unwrap_or_default(),
 
             engine: engine,
+            engine_events: engine_events,
+            turn_events: vec![],
             automoving: None,
             automoving_stopped_turn: 0,
 
@@ -221,6 +261,7 @@ impl Ui {
             next_anim_frame_ts: Instant::now(),
             game_action_queue: VecDeque::new(),
             map_renderer: MapRenderer::new(UNICODE_DOT),
+            replay: None,
         };
         ui.display_intro();
         ui.resize();
@@ -264,11 +305,13 @@ impl Ui {
 
     pub fn redraw_now(&mut self) {
         match self.mode {
-            Mode::Normal | Mode::Examine | Mode::Inventory(_) | Mode::Target(_) | Mode::GoTo => {
-                if let Mode::Inventory(_) = self.mode {
-                    self.draw_inventory();
-                } else {
-                    self.draw_map();
+            Mode::Normal | Mode::Examine | Mode::Inventory(_) | Mode::Target(_) | Mode::GoTo |
+            Mode::Spells | Mode::Pile(_) => {
+                match self.mode {
+                    Mode::Inventory(_) => self.draw_inventory(),
+                    Mode::Spells => self.draw_spells(),
+                    Mode::Pile(page) => self.draw_pile(page),
+                    _ => self.draw_map(),
                 }
 
                 self.draw_log();
@@ -286,9 +329,21 @@ impl Ui {
                     FSMode::Intro => {
                         self.draw_intro();
                     }
+                    FSMode::Resume => {
+                        self.draw_resume();
+                    }
                     FSMode::PickRace => {
                         self.draw_pickrace();
                     }
+                    FSMode::GameOver => {
+                        self.draw_game_over();
+                    }
+                    FSMode::HighScores => {
+                        self.draw_high_scores();
+                    }
+                    FSMode::LevelUp => {
+                        self.draw_level_up();
+                    }
                 }
             }
         }
@@ -307,7 +362,7 @@ impl Ui {
         let player = self.player();
         let cur_loc = self.current_location();
 
-        !player.was_attacked_by.is_empty() || player.discovered_areas.iter().any(|_| true) ||
+        self.player_was_attacked() || player.discovered_areas.iter().any(|_| true) ||
         player.visible.iter().any(|&coord| {
             cur_loc.at(coord)
                    .actor_map_or(false, |a| a.race == actor::Race::Rat)
@@ -321,6 +376,15 @@ impl Ui {
               .any(|(c, _)| !player.sees(*c)) || player.discovered_stairs(cur_loc)
     }
 
+    fn player_was_attacked(&self) -> bool {
+        let player_id = self.current_location().player_id();
+
+        self.turn_events.iter().any(|event| match *event {
+            game::Event::Attacked(ref attack) => attack.target == player_id,
+            _ => false,
+        })
+    }
+
     pub fn automove_action(&self, movetype: AutoMoveType) -> AutoMoveAction {
         let player = self.player();
         let cur_loc = self.current_location();
@@ -337,9 +401,13 @@ impl Ui {
         }
     }
 
-    fn goto_action(&self, _gototype: GoToType) -> AutoMoveAction {
+    fn goto_action(&self, gototype: GoToType) -> AutoMoveAction {
         let player = self.player();
         let cur_loc = self.current_location();
+        let feature = match gototype {
+            GoToType::Stairs => tile::Feature::Stairs,
+            GoToType::StairsUp => tile::Feature::StairsUp,
+        };
 
         let start = player.pos.coord;
 
@@ -349,8 +417,7 @@ impl Ui {
                                                player.knows(c))
                                           },
                                           |c| {
-                                              cur_loc.at(c).tile().feature ==
-                                              Some(tile::Feature::Stairs)
+                                              cur_loc.at(c).tile().feature == Some(feature)
                                           },
                                           start);
 
@@ -414,7 +481,17 @@ impl Ui {
         self.automoving_stopped_turn = self.engine.turn()
     }
 
+    /// Replace the game, eg. with a loaded one
+    fn set_engine(&mut self, engine: game::Engine) {
+        self.engine = engine;
+        self.engine_events = self.engine.subscribe();
+        self.turn_events.clear();
+    }
+
     fn engine_change(&mut self, actor_id: actor::Id) {
+        let events = self.engine.poll_events(self.engine_events);
+        self.turn_events.extend(events);
+
         if self.automoving.is_some() &&
             self.automoving_stopped_turn != self.engine.turn() && self.should_stop_automoving() {
                 self.automoving_stop();
@@ -448,17 +525,74 @@ impl Ui {
         self.next_anim_frame_ts <= Instant::now()
     }
 
+    fn invalid_action_msg(&self, action: game::Action) -> &'static str {
+        match action {
+            Ascend if self.current_location().level == 0 &&
+                      self.current_location().at(self.player().coord()).tile().feature ==
+                      Some(tile::StairsUp) => "You can't leave the dungeon without the orb.",
+            Equip(ch) if self.player().blocked_by_curse(ch, true) => {
+                "You can't take off a cursed item."
+            }
+            Drop_(ch) if self.player().blocked_by_curse(ch, false) => {
+                "You can't take off a cursed item."
+            }
+            Equip(ch) if self.player().items_backpack.contains_key(&ch) => "You can't equip that.",
+            Equip(_) | Drop_(_) => "You don't have that.",
+            Ranged(_) if self.player().is_holding_ranged_weapon() => "You have no arrows.",
+            Ranged(_) => "You don't hold a ranged weapon.",
+            Pick(i) if self.current_location().at(self.player().head()).items().len() > i => {
+                "Your backpack is full."
+            }
+            Pick(_) => "There's nothing to pick up.",
+            Shove => "There's nobody to shove.",
+            Descend => "There are no stairs down here.",
+            Ascend => "There are no stairs up here.",
+            Cast(spell, _) if self.player().mp < spell.mp_cost() => "You don't have enough MP.",
+            Cast(..) => "You can't blink there.",
+            Throw(..) => "You can't throw that there.",
+            _ => "You can't do that.",
+        }
+    }
+
+    /// Pass player's action to the engine; false if it was refused
+    fn player_act(&mut self, action: game::Action) -> bool {
+        let player_id = self.current_location().player_id();
+
+        match self.engine.player_act(action) {
+            Ok(()) => {
+                self.engine_change(player_id);
+                true
+            }
+            Err(game::error::Error::InvalidAction(action)) => {
+                if self.replay.is_some() {
+                    self.replay_stop("Replay out of sync. Stopped.");
+                } else {
+                    let msg = self.invalid_action_msg(action);
+                    self.log(msg);
+                    self.redraw();
+                }
+                false
+            }
+            Err(_) => false,
+        }
+    }
+
     pub fn run_engine_turn(&mut self) {
-        if self.spawned && self.is_next_turn_time() {
+        if self.spawned && !self.game_over && self.is_next_turn_time() {
             let player_id = self.current_location().player_id();
+            let attr_points = self.player().attr_points;
             let mut player_acted = false;
 
             if self.engine.is_turn_done() {
-                self.engine.start_turn()
+                let _ = self.engine.start_turn();
             }
 
             if self.engine.needs_player_input() {
-                if let Some(movetype) = self.automoving {
+                if self.replay.is_some() {
+                    if let Some(action) = self.replay_next_action() {
+                        player_acted = self.player_act(action);
+                    }
+                } else if let Some(movetype) = self.automoving {
                     match self.automove_action(movetype) {
                         AutoMoveAction::Blocked => {
                             match movetype {
@@ -470,9 +604,10 @@ impl Ui {
                             self.automoving_stop();
                         }
                         AutoMoveAction::Action(action) => {
-                            self.engine.player_act(action);
-                            self.engine_change(player_id);
-                            player_acted = true;
+                            player_acted = self.player_act(action);
+                            if !player_acted {
+                                self.automoving_stop();
+                            }
                         }
                         AutoMoveAction::Finish => {
                             if let AutoMoveType::Explore = movetype {
@@ -482,27 +617,133 @@ impl Ui {
                         }
                     }
                 } else if let Some(action) = self.game_action_queue.pop_front() {
-                    self.engine.player_act(action);
-                    self.engine_change(player_id);
-                    player_acted = true;
+                    player_acted = self.player_act(action);
                 }
             } else {
-                self.engine.player_skip_act();
+                self.replay_next_skip();
+                player_acted = self.engine.player_skip_act().is_ok();
                 self.engine_change(player_id);
-                player_acted = true;
             }
 
             if player_acted {
-                while !self.engine.is_turn_done() {
-                    let actor_id = self.engine.one_actor_tick();
+                while let Ok(actor_id) = self.engine.one_actor_tick() {
                     self.engine_change(actor_id);
                 }
                 self.update_changes();
                 self.redraw();
+
+                if self.player().attr_points > attr_points && self.replay.is_none() &&
+                   !self.player().is_dead() {
+                    self.automoving_stop();
+                    self.game_action_queue.clear();
+                    self.mode_switch_to(Mode::FullScreen(FSMode::LevelUp));
+                }
+            }
+
+            if self.player().is_dead() || self.engine.is_won() {
+                self.game_over();
             }
         }
     }
 
+    /// The player is dead or has won: record the run and show how it went
+    fn game_over(&mut self) {
+        self.game_over = true;
+        self.automoving = None;
+        self.game_action_queue.clear();
+
+        if self.replay.is_none() {
+            let path = format!("morgue-{}.txt", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+            let res = morgue::write(&path, &self.engine, &self.log.borrow());
+            match res {
+                Ok(()) => self.game_over_morgue = Some(path),
+                Err(_) => self.log("Failed to write the morgue file."),
+            }
+
+            self.game_over_rank = self.high_scores.add(score::Entry::new(&self.engine));
+            if self.high_scores.save(HIGH_SCORES_FILE).is_err() {
+                self.log("Failed to save the high scores.");
+            }
+        }
+
+        self.mode_switch_to(Mode::FullScreen(FSMode::GameOver));
+    }
+
+    /// Start replaying a recorded game from the beginning
+    pub fn start_replay(&mut self, journal: Journal, delay_ms: u64) {
+        let race = match journal.race {
+            Some(race) => race,
+            None => {
+                self.log("Nothing to replay.");
+                return;
+            }
+        };
+
+        self.set_engine(game::Engine::new(journal.seed));
+        self.replay = Some(Replay::new(journal, delay_ms));
+        self.initial_spawn(race);
+        self.log("Replay: [space] pause, [.] step, [+/-] speed, [f] fast forward.");
+        self.mode_switch_to(Mode::Normal);
+    }
+
+    fn replay_stop(&mut self, reason: &str) {
+        self.replay = None;
+        self.log(reason);
+    }
+
+    /// Next action of the replayed game, if it is time for it already
+    fn replay_next_action(&mut self) -> Option<game::Action> {
+        let turn = self.engine.turn();
+        let entry = match self.replay {
+            Some(ref mut replay) if replay.can_advance() => replay.pop(),
+            _ => return None,
+        };
+
+        match entry {
+            Some(Entry { turn: entry_turn, input: Input::Act(action) }) if entry_turn == turn => {
+                let delay = self.replay.as_ref().map_or(Duration::from_millis(0), |r| r.delay());
+                self.next_turn_ts = Instant::now() + delay;
+                Some(action)
+            }
+            Some(Entry { turn: entry_turn, input: Input::Raise(attr) }) if entry_turn == turn => {
+                // takes no time; the action will come with the next entry
+                if self.engine.player_raise_attribute(attr).is_err() {
+                    self.replay_stop("Replay out of sync. Stopped.");
+                }
+                None
+            }
+            Some(_) => {
+                self.replay_stop("Replay out of sync. Stopped.");
+                None
+            }
+            None => {
+                self.replay_stop("End of replay.");
+                None
+            }
+        }
+    }
+
+    /// Consume the recorded skip matching `Engine::player_skip_act`
+    fn replay_next_skip(&mut self) {
+        let in_sync = match self.replay {
+            Some(ref mut replay) => {
+                match replay.peek().map(|entry| entry.input) {
+                    Some(Input::Skip) => {
+                        replay.discard();
+                        true
+                    }
+                    Some(Input::Act(_)) | Some(Input::Raise(_)) => false,
+                    None => true,
+                }
+            }
+            None => return,
+        };
+
+        if !in_sync {
+            self.replay_stop("Replay out of sync. Stopped.");
+        }
+    }
+
     pub fn maybe_redraw_now(&mut self) {
         if self.is_next_anim_frame_time() {
             self.redraw_now();
@@ -596,8 +837,15 @@ impl Ui {
             (KEY_LOWS, false) |
             (nc::KEY_DOWN, _) => Game(Move(Back)),
             (KEY_DOT, _) => Game(Wait),
-            (KEY_COMMA, _) => Game(Pick),
+            (KEY_COMMA, _) => {
+                if self.current_location().at(self.player().head()).items().len() > 1 {
+                    ModeSwitch(Mode::Pile(0))
+                } else {
+                    Game(Pick(0))
+                }
+            }
             (KEY_DESCEND, _) => Game(Descend),
+            (KEY_ASCEND, _) => Game(Ascend),
             (KEY_LOWO, _) => AutoMove(AutoMoveType::Explore),
             (KEY_CAPK, true) => AutoMove(AutoMoveType::Walk),
             (KEY_CAPW, false) => AutoMove(AutoMoveType::Walk),
@@ -608,6 +856,10 @@ impl Ui {
             (KEY_CAPD, _) => ModeSwitch(Mode::Inventory(InvMode::Drop_)),
             (KEY_LOWX, _) => ModeSwitch(Mode::Examine),
             (KEY_LOWF, _) => ModeSwitch(Mode::Target(TargetMode::Ranged)),
+            (KEY_CAST, _) => ModeSwitch(Mode::Spells),
+            (KEY_LOWT, _) => ModeSwitch(Mode::Inventory(InvMode::Throw)),
+            (KEY_LOWP, _) => Game(Shove),
+            (KEY_CAPA, _) => ModeSwitch(Mode::FullScreen(FSMode::LevelUp)),
             (KEY_HELP, _) => ModeSwitch(Mode::FullScreen(FSMode::Help)),
             (KEY_GOTO, _) => ModeSwitch(Mode::GoTo),
             _ => { return None}
@@ -630,15 +882,43 @@ impl Ui {
                 match fs_mode {
                     FSMode::Quit => {
                         match key {
-                            KEY_LOWY | KEY_CAPY => self.exit = true,
+                            KEY_LOWY | KEY_CAPY => self.save_and_exit(),
                             _ => self.mode_switch_to(Mode::Normal),
                         }
                     }
+                    FSMode::Resume => {
+                        match key {
+                            KEY_LOWY | KEY_CAPY => self.resume(),
+                            KEY_LOWN | KEY_CAPN => {
+                                self.mode_switch_to(Mode::FullScreen(FSMode::Intro))
+                            }
+                            _ => {}
+                        }
+                    }
                     FSMode::Intro => {
                         match key {
+                            KEY_LOWH => self.mode_switch_to(Mode::FullScreen(FSMode::HighScores)),
                             _ => self.mode_switch_to(Mode::FullScreen(FSMode::PickRace)),
                         }
                     }
+                    FSMode::GameOver => {
+                        self.mode_switch_to(Mode::FullScreen(FSMode::HighScores))
+                    }
+                    FSMode::HighScores => {
+                        if self.game_over {
+                            self.save_and_exit()
+                        } else {
+                            self.mode_switch_to(Mode::FullScreen(FSMode::Intro))
+                        }
+                    }
+                    FSMode::LevelUp => {
+                        match key {
+                            KEY_LOWA => self.raise_attribute(Attribute::Str),
+                            KEY_LOWB => self.raise_attribute(Attribute::Int),
+                            KEY_LOWC => self.raise_attribute(Attribute::Dex),
+                            _ => self.mode_switch_to(Mode::Normal),
+                        }
+                    }
                     FSMode::PickRace => {
                         match key {
                             KEY_LOWA => {
@@ -663,6 +943,7 @@ impl Ui {
                     }
                 }
             }
+            Mode::Normal if self.replay.is_some() => self.input_handle_key_replay(key),
             Mode::Normal => if let Some(action) = self.key_to_action_normal(key) {
                 match action {
                     Game(action) => self.queue_action(action),
@@ -670,6 +951,18 @@ impl Ui {
                         if self.player().can_attack_ranged() {
                             self.target_pos = None;
                             self.mode_switch_to(mode);
+                        } else {
+                            let msg = self.invalid_action_msg(Ranged(self.player().coord()));
+                            self.log(msg);
+                            self.redraw();
+                        }
+                    },
+                    ModeSwitch(Mode::Spells) => {
+                        if self.player().spells.is_empty() {
+                            self.log("You don't know any spells.");
+                            self.redraw();
+                        } else {
+                            self.mode_switch_to(Mode::Spells);
                         }
                     },
                     ModeSwitch(mode) => {
@@ -693,6 +986,51 @@ impl Ui {
                     }
                 }
             }
+            Mode::Spells => {
+                let ch = key as u8 as char;
+                match key {
+                    KEY_ESC => self.mode_switch_to(Mode::Normal),
+                    _ => match ch {
+                        'a'..='z' => {
+                            let spell = self.player().spells.get((ch as u8 - b'a') as usize).cloned();
+                            if let Some(spell) = spell {
+                                if spell.needs_target() {
+                                    self.target_pos = None;
+                                    self.mode_switch_to(Mode::Target(TargetMode::Spell(spell)));
+                                } else {
+                                    self.queue_action(Cast(spell, None));
+                                    self.mode_switch_to(Mode::Normal);
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Mode::Pile(page) => {
+                let ch = key as u8 as char;
+                let pages = (self.pile_in_front().len() + PILE_PAGE - 1) / PILE_PAGE;
+                match key {
+                    KEY_ESC => self.mode_switch_to(Mode::Normal),
+                    KEY_DESCEND | nc::KEY_NPAGE if page + 1 < pages => {
+                        self.mode_switch_to(Mode::Pile(page + 1))
+                    }
+                    KEY_ASCEND | nc::KEY_PPAGE if page > 0 => {
+                        self.mode_switch_to(Mode::Pile(page - 1))
+                    }
+                    _ => match ch {
+                        'a'..='z' => {
+                            let shown = page * PILE_PAGE + (ch as u8 - b'a') as usize;
+                            let i = self.pile_in_front().get(shown).map(|&(i, _)| i);
+                            if let Some(i) = i {
+                                self.queue_action(Pick(i));
+                                self.mode_switch_to(Mode::Normal);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
             Mode::Inventory(InvMode::View) => {
                 let ch = key as u8 as char;
                 match key {
@@ -703,6 +1041,21 @@ impl Ui {
                     }
                 }
             }
+            Mode::Inventory(InvMode::Throw) => {
+                let ch = key as u8 as char;
+                match key {
+                    KEY_ESC => self.mode_switch_to(Mode::Normal),
+                    _ => match ch {
+                        'a'..='z' | 'A'..='Z' => {
+                            if self.player().items_backpack.contains_key(&ch) {
+                                self.target_pos = None;
+                                self.mode_switch_to(Mode::Target(TargetMode::Throw(ch)));
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
             Mode::Inventory(InvMode::Drop_) => {
                 let ch = key as u8 as char;
                 match key {
@@ -753,7 +1106,7 @@ impl Ui {
                     _ => {}
                 }
             }
-            Mode::Target(_) => {
+            Mode::Target(target_mode) => {
                 let center = self.player().pos;
                 let pos = self.target_pos.unwrap_or(center);
 
@@ -763,10 +1116,16 @@ impl Ui {
                         self.mode_switch_to(Mode::Normal);
                     }
                     KEY_ENTER | KEY_LOWF => {
-                        match self.target_pos
+                        match (self.target_pos, target_mode)
                         {
-                            Some(target) => { self.queue_ranged(target.coord); }
-                            None => { /* Nothing */ }
+                            (Some(target), TargetMode::Ranged) => { self.queue_ranged(target.coord); }
+                            (Some(target), TargetMode::Spell(spell)) => {
+                                self.queue_action(Cast(spell, Some(target.coord)));
+                            }
+                            (Some(target), TargetMode::Throw(ch)) => {
+                                self.queue_action(Throw(ch, target.coord));
+                            }
+                            (None, _) => { /* Nothing */ }
                         }
                         self.target_pos = None;
                         self.mode_switch_to(Mode::Normal);
@@ -791,14 +1150,69 @@ impl Ui {
                 }
             }
             Mode::GoTo => {
-                if let KEY_DESCEND = key {
-                    self.automoving = Some(AutoMoveType::GoTo(GoToType::Stairs))
+                match key {
+                    KEY_DESCEND => self.automoving = Some(AutoMoveType::GoTo(GoToType::Stairs)),
+                    KEY_ASCEND => self.automoving = Some(AutoMoveType::GoTo(GoToType::StairsUp)),
+                    _ => {}
                 }
                 self.mode_switch_to(Mode::Normal);
             }
         }
     }
 
+    /// Spend an attribute point, leaving the level-up screen once all are spent
+    fn raise_attribute(&mut self, attr: Attribute) {
+        if self.engine.is_turn_done() {
+            let _ = self.engine.start_turn();
+        }
+
+        match self.engine.player_raise_attribute(attr) {
+            Ok(()) => {
+                let stats = &self.player().base_stats;
+                let val = match attr {
+                    Attribute::Str => stats.str_,
+                    Attribute::Int => stats.int,
+                    Attribute::Dex => stats.dex,
+                };
+                self.log(&format!("Your {} rises to {}.", attr.description(), val));
+
+                if self.player().attr_points == 0 {
+                    self.mode_switch_to(Mode::Normal);
+                } else {
+                    self.redraw();
+                }
+            }
+            Err(game::error::Error::NoAttributePoints) => {
+                self.log("You have no attribute points to spend.");
+                self.mode_switch_to(Mode::Normal);
+            }
+            Err(_) => {
+                self.log("You can't do that now.");
+                self.mode_switch_to(Mode::Normal);
+            }
+        }
+    }
+
+    fn input_handle_key_replay(&mut self, key : i32) {
+        match key {
+            KEY_ESC | KEY_LOWQ => self.mode_switch_to(Mode::FullScreen(FSMode::Quit)),
+            KEY_HELP => self.mode_switch_to(Mode::FullScreen(FSMode::Help)),
+            _ => {
+                if let Some(ref mut replay) = self.replay {
+                    match key {
+                        KEY_SPACE => replay.toggle_pause(),
+                        KEY_DOT => replay.step(),
+                        KEY_PLUS => replay.faster(),
+                        KEY_MINUS => replay.slower(),
+                        KEY_LOWF => replay.toggle_fast_forward(),
+                        _ => {}
+                    }
+                }
+                self.redraw();
+            }
+        }
+    }
+
     pub fn input_handle_key(&mut self, key : i32) {
         if let Some(_) = self.key_to_action_global(key) {
             self.input_mode.toggle();
@@ -810,6 +1224,7 @@ impl Ui {
 
     // Notice changed between start turn and it's end
     fn update_changes(&mut self) {
+        let events = mem::replace(&mut self.turn_events, vec![]);
 
         let cur_loc = self.current_location();
         let player = self.player();
@@ -826,45 +1241,138 @@ impl Ui {
             self.log(&s);
         }
 
-        for item_coord in player.discovered
-                                .iter()
-                                .filter(|&coord| cur_loc.at(*coord).item_map_or(false, |_| true)) {
-            let item_descr = cur_loc.at(*item_coord)
-                                    .item_map_or("".to_owned(), |i| i.description());
-            self.log(&format!("You've found {}.", item_descr));
+        for item in player.discovered
+                          .iter()
+                          .flat_map(|&coord| cur_loc.at(coord).items()) {
+            if let Some(artifact) = item.artifact() {
+                self.log(&format!("You've found {}, a legendary {}!",
+                                  artifact.name(),
+                                  artifact.type_()));
+                continue;
+            }
+
+            self.log(&format!("You've found {}.", self.item_name(item)));
         }
 
         if player.discovered_stairs(cur_loc) {
             self.log("You've found stairs.");
         }
 
-        for res in &player.was_attacked_by {
-            if res.success {
-                self.log(&format!("{} hit you {}for {} dmg.",
-                                  res.who,
-                                  if res.behind {
-                                      "from behind "
-                                  } else {
-                                      ""
-                                  },
-                                  res.dmg));
-            } else {
-                self.log(&format!("{} missed you.", res.who));
+        let player_id = cur_loc.player_id();
+        let who = |id| cur_loc.actors_byid.get(&id).map_or("something".to_owned(), |a| a.description());
+        let seen = |id| cur_loc.actors_byid.get(&id).map_or(false, |a| player.sees(a.coord()));
+
+        for event in &events {
+            let attack = match *event {
+                game::Event::Attacked(ref attack) => attack,
+                game::Event::OpportunityAttack { attacker, target } => {
+                    if target == player_id {
+                        self.log(&format!("{} attacks you as you move away.", who(attacker)));
+                    } else if attacker == player_id {
+                        self.log(&format!("You attack {} as it moves away.", who(target)));
+                    }
+                    continue;
+                }
+                _ => continue,
+            };
+            let arc = attack.arc.description();
+
+            if attack.target == player_id {
+                if attack.success {
+                    self.log(&format!("{} hit your {} for {} dmg.",
+                                      who(attack.attacker),
+                                      arc,
+                                      attack.dmg_dealt()));
+                } else if attack.shielded {
+                    self.log(&format!("You blocked {} with your shield.", who(attack.attacker)));
+                } else {
+                    self.log(&format!("{} missed you.", who(attack.attacker)));
+                }
+            } else if attack.attacker == player_id {
+                if attack.success {
+                    self.log(&format!("You hit {}'s {} for {} dmg.",
+                                      who(attack.target),
+                                      arc,
+                                      attack.dmg_dealt()));
+                } else if attack.shielded {
+                    self.log(&format!("{} blocked you with a shield.", who(attack.target)));
+                } else {
+                    self.log(&format!("You missed {}.", who(attack.target)));
+                }
             }
         }
 
-        for res in &player.did_attack {
-            if res.success {
-                self.log(&format!("You hit {} {}for {} dmg.",
-                                  res.who,
-                                  if res.behind {
-                                      "from behind "
-                                  } else {
-                                      ""
-                                  },
-                                  res.dmg));
-            } else {
-                self.log(&format!("You missed {}.", res.who));
+        for event in &events {
+            match *event {
+                game::Event::Shoved { id, target, success: false } if id == player_id => {
+                    self.log(&format!("You fail to shove {}.", who(target)));
+                }
+                game::Event::Pushed { id, to: Some(_), .. } if id == player_id => {
+                    self.log("You are knocked back.");
+                }
+                game::Event::Pushed { by, id, to: Some(_), .. } if by == player_id => {
+                    self.log(&format!("You knock {} back.", who(id)));
+                }
+                game::Event::Collided { id, obstacle, dmg } if id == player_id || seen(id) => {
+                    let what = match obstacle {
+                        Obstacle::Wall => "an obstacle".to_owned(),
+                        Obstacle::Water => "the water".to_owned(),
+                        Obstacle::Actor(other) => who(other),
+                    };
+                    let subject = if id == player_id {
+                        "You".to_owned()
+                    } else {
+                        who(id)
+                    };
+                    self.log(&format!("{} crashed into {} for {} dmg.", subject, what, dmg));
+                }
+                game::Event::ItemThrown { id, ref item, .. } if id == player_id => {
+                    if item.shatters() {
+                        self.log(&format!("You throw {}. It shatters.", self.item_name(item)));
+                    } else {
+                        self.log(&format!("You throw {}.", self.item_name(item)));
+                    }
+                }
+                game::Event::ItemUsed { id, ref item } if id == player_id => {
+                    self.log(&format!("You use {}.", self.item_name(item)));
+                }
+                game::Event::ItemEquipped { id, ref item, .. } if id == player_id &&
+                                                                  item.is_cursed() => {
+                    self.log(&format!("The {} is cursed!", item.type_()));
+                }
+                game::Event::ItemIdentified { id, ref item } if id == player_id => {
+                    self.log(&format!("You've identified {}.", self.item_name(item)));
+                }
+                game::Event::SpellCast { id, spell } if id == player_id => {
+                    self.log(&format!("You cast {}.", spell.description()));
+                }
+                game::Event::SpellHit { attacker, target, spell, dmg } if target == player_id => {
+                    self.log(&format!("{}'s {} hit you for {} dmg.",
+                                      who(attacker),
+                                      spell.description(),
+                                      dmg));
+                }
+                game::Event::SpellHit { attacker, target, spell, dmg } if attacker == player_id => {
+                    self.log(&format!("Your {} hit {} for {} dmg.",
+                                      spell.description(),
+                                      who(target),
+                                      dmg));
+                }
+                game::Event::StatusGained { id, status } if id == player_id => {
+                    self.log(&format!("You are {}.", status.description()));
+                }
+                game::Event::StatusEnded { id, status } if id == player_id => {
+                    self.log(&format!("You are no longer {}.", status.description()));
+                }
+                game::Event::LevelUp { id, level } if id == player_id => {
+                    self.log(&format!("You have reached level {}.", level));
+                }
+                game::Event::SkillImproved { id, skill, level } if id == player_id => {
+                    self.log(&format!("Your {} skill has improved to {}.",
+                                      skill.description(),
+                                      level));
+                }
+                _ => {}
             }
         }
 
@@ -888,7 +1396,65 @@ impl Ui {
     }
 
     pub fn display_intro(&mut self) {
-        self.mode = Mode::FullScreen(FSMode::Intro);
+        self.mode = if game::save::exists(SAVE_FILE) {
+            Mode::FullScreen(FSMode::Resume)
+        } else {
+            Mode::FullScreen(FSMode::Intro)
+        };
+    }
+
+    /// Continue the game stored in `SAVE_FILE`
+    ///
+    /// The save is removed once loaded, so every game can be resumed only once.
+    pub fn resume(&mut self) {
+        match game::save::load(SAVE_FILE) {
+            Ok(engine) => {
+                let _ = game::save::remove(SAVE_FILE);
+                self.set_engine(engine);
+                let player_id = self.engine.current_location().player_id();
+                self.engine_change(player_id);
+                self.spawned = true;
+                self.next_turn_ts = Instant::now();
+                self.log("Welcome back.");
+                self.mode_switch_to(Mode::Normal);
+            }
+            Err(err) => {
+                // don't offer to resume it again on every start
+                let fate = if game::save::rename(SAVE_FILE, BAD_SAVE_FILE).is_ok() {
+                    format!("moved to {}", BAD_SAVE_FILE)
+                } else if game::save::remove(SAVE_FILE).is_ok() {
+                    "removed".to_owned()
+                } else {
+                    "left in place".to_owned()
+                };
+                self.log(&format!("Failed to load the saved game ({}); it was {}.", err, fate));
+                self.mode_switch_to(Mode::FullScreen(FSMode::Intro));
+            }
+        }
+    }
+
+    /// Save the game in progress (if any) and exit
+    ///
+    /// The journal of the game is written to `REPLAY_FILE`, unless it is
+    /// a replay itself.
+    pub fn save_and_exit(&mut self) {
+        if self.replay.is_some() {
+            self.exit = true;
+            return;
+        }
+
+        if self.spawned {
+            let _ = self.engine.journal().save(REPLAY_FILE);
+        }
+
+        if self.spawned && !self.player().is_dead() && !self.engine.is_won() {
+            if game::save::save(&self.engine, SAVE_FILE).is_err() {
+                self.log("Failed to save the game.");
+                self.mode_switch_to(Mode::Normal);
+                return;
+            }
+        }
+        self.exit = true;
     }
 
     fn draw_map(&mut self) {
@@ -974,12 +1540,26 @@ impl Ui {
         }
 
         if let Some(&(_, ref item)) = astate.items_equipped.get(&slot) {
-            nc::waddstr(window, &format!("{:^13}", item.description()));
+            nc::waddstr(window, &format!("{:^13}", self.item_name(item)));
         } else {
             nc::waddch(window, '-' as nc::chtype);
         }
     }
 
+    /// Name of an item as far as the player knows
+    fn item_name(&self, item: &Item) -> String {
+        item.known(self.engine.knowledge()).to_string()
+    }
+
+    /// `item_name`, marking the items that might still hide something
+    fn item_inventory_name(&self, item: &Item) -> String {
+        if item.is_identified() {
+            self.item_name(item)
+        } else {
+            format!("{} (unidentified)", self.item_name(item))
+        }
+    }
+
     fn draw_inventory(&self) {
         self.windows.map.clear(&self.calloc);
         let window = self.windows.map.window;
@@ -992,7 +1572,7 @@ impl Ui {
             nc::waddstr(window, &format!("Equipped: \n"));
             for (slot, &(ref ch, ref i)) in &player.items_equipped {
                 nc::waddstr(window,
-                            &format!(" {} - {} [{:?}]\n", ch, i.description(), slot));
+                            &format!(" {} - {} [{:?}]\n", ch, self.item_inventory_name(i), slot));
             }
             nc::waddstr(window, &format!("\n"));
         }
@@ -1000,7 +1580,7 @@ impl Ui {
         nc::waddstr(window, &format!("Inventory: \n"));
         if !player.items_backpack.is_empty() {
             for (ch, i) in &player.items_backpack {
-                nc::waddstr(window, &format!(" {} - {}\n", ch, i.description()));
+                nc::waddstr(window, &format!(" {} - {}\n", ch, self.item_inventory_name(i)));
             }
         }
 
@@ -1009,6 +1589,85 @@ impl Ui {
         nc::wnoutrefresh(window);
     }
 
+    fn status_abbrev(status: Status) -> &'static str {
+        match status {
+            Status::Poisoned => "Pois",
+            Status::Bleeding => "Bleed",
+            Status::Stunned => "Stun",
+            Status::Slowed => "Slow",
+            Status::Hasted => "Haste",
+            Status::Blinded => "Blind",
+            Status::Confused => "Conf",
+        }
+    }
+
+    fn draw_spells(&self) {
+        self.windows.map.clear(&self.calloc);
+        let window = self.windows.map.window;
+        let player = self.player();
+
+        nc::wmove(window, 0, 0);
+
+        nc::wattron(window, self.text_color);
+        nc::waddstr(window, "Spells: \n");
+        for (i, spell) in player.spells.iter().enumerate() {
+            if player.can_cast(*spell) {
+                nc::wattron(window, self.text_color);
+            } else {
+                nc::wattron(window, self.text_gray_color);
+            }
+            nc::waddstr(window,
+                        &format!(" {} - {} ({} MP)\n",
+                                 (b'a' + i as u8) as char,
+                                 spell.description(),
+                                 spell.mp_cost()));
+        }
+
+        nc::wattron(window, self.text_color);
+        nc::waddstr(window, &format!("\n[ESC] to close...\n"));
+
+        nc::wnoutrefresh(window);
+    }
+
+    /// The pile in front with the indices of the items, top first
+    fn pile_in_front(&self) -> Vec<(usize, &Item)> {
+        self.current_location()
+            .at(self.player().head())
+            .items()
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(i, item)| (i, &**item))
+            .collect()
+    }
+
+    fn draw_pile(&self, page: usize) {
+        self.windows.map.clear(&self.calloc);
+        let window = self.windows.map.window;
+        let pile = self.pile_in_front();
+
+        nc::wmove(window, 0, 0);
+
+        nc::wattron(window, self.text_color);
+        nc::waddstr(window, "Items in front: \n");
+        for (shown, &(_, item)) in pile.iter().enumerate().skip(page * PILE_PAGE).take(PILE_PAGE) {
+            nc::waddstr(window,
+                        &format!(" {} - {}\n",
+                                 (b'a' + (shown % PILE_PAGE) as u8) as char,
+                                 self.item_name(item)));
+        }
+
+        if pile.len() > PILE_PAGE {
+            let pages = (pile.len() + PILE_PAGE - 1) / PILE_PAGE;
+            nc::waddstr(window,
+                        &format!("\nPage {}/{}, < and > to turn\n", page + 1, pages));
+        }
+
+        nc::waddstr(window, &format!("\n[ESC] to close...\n"));
+
+        nc::wnoutrefresh(window);
+    }
+
     fn draw_stats(&self) {
         self.windows.stats.clear(&self.calloc);
         let turn = self.engine.turn();
@@ -1040,6 +1699,23 @@ impl Ui {
         y += 1;
         nc::wmove(window, y, 0);
         self.draw_val(window, "Dex", player.stats.base.dex);
+        nc::wmove(window, y, 7);
+        self.draw_val(window, "SPD", player.stats.base.speed);
+        nc::wmove(window, y, 15);
+        self.draw_val(window, "XL", player.xp_level);
+
+        y += 1;
+        nc::wmove(window, y, 0);
+        self.draw_label(window, "XP");
+        nc::wattron(window, self.text_color);
+        nc::waddstr(window,
+                    &format!(" {}/{} ",
+                             player.xp,
+                             actor::xp_for_level(player.xp_level + 1)));
+        if player.attr_points > 0 {
+            nc::wattron(window, self.green_color);
+            nc::waddstr(window, &format!("+{} (A)", player.attr_points));
+        }
 
         y += 1;
         self.draw_stats_bar(window, y,
@@ -1062,6 +1738,17 @@ impl Ui {
                             player.saved_sp,
                             player.stats.base.max_sp);
 
+        y += 1;
+        nc::wmove(window, y, 0);
+        for (status, _) in player.statuses.iter() {
+            if status.is_beneficial() {
+                nc::wattron(window, self.green_color);
+            } else {
+                nc::wattron(window, self.red_color);
+            }
+            nc::waddstr(window, &format!("{} ", Ui::status_abbrev(*status)));
+        }
+
         let slots = [("R", Slot::RHand),
                      ("L", Slot::LHand),
                      ("F", Slot::Feet),
@@ -1101,6 +1788,10 @@ impl Ui {
         self.draw_turn(window, "Turn", turn);
         self.draw_turn(window, "Level", cur_loc.level);
 
+        y += 1;
+        nc::wmove(window, y, 0);
+        self.draw_turn(window, "Seed", self.engine.seed());
+
         nc::wnoutrefresh(window);
     }
 
@@ -1161,20 +1852,37 @@ impl Ui {
         let tile_type = gstate.at(coord).tile().type_;
         let tile = gstate.at(coord).tile();
         let feature_descr = tile.feature.map(|f| f.description().to_owned());
-        let item_descr = gstate.at(coord).item_map_or(None, |i| Some(i.description().to_owned()));
+        let items = gstate.at(coord).items();
+        let item_descr = if items.is_empty() {
+            None
+        } else {
+            Some(items.iter().rev().map(|i| self.item_name(i)).collect::<Vec<_>>().join(", "))
+        };
 
         let actor_descr = if astate.sees(coord) || astate.is_dead() {
             gstate.at(coord).actor_map_or(None, |a| {
-                Some(match a.race {
-                         // Race::Pony => "A Pony",
-                         Race::Rat => "A rat",
-                         Race::Goblin => "Goblin",
-                         Race::Troll => "Troll",
-                         Race::Human => "Human",
-                         Race::Elf => "Elf",
-                         Race::Dwarf => "Dwarf",
-                     }
-                     .to_owned())
+                let name = match a.race {
+                    // Race::Pony => "A Pony",
+                    Race::Rat => "A rat",
+                    Race::Goblin => "Goblin",
+                    Race::Troll => "Troll",
+                    Race::Human => "Human",
+                    Race::Elf => "Elf",
+                    Race::Dwarf => "Dwarf",
+                };
+
+                // which side of it would be attacked from here
+                Some(match astate.coord().direction_to_cw(coord) {
+                    Some(dir) if a.id != astate.id => {
+                        let arc = Arc::of_attack(dir, a.pos.dir);
+                        let (_, _, shielded) = a.defense(arc);
+                        format!("{} ({}{})",
+                                name,
+                                arc.description(),
+                                if shielded { ", shielded" } else { "" })
+                    }
+                    _ => name.to_owned(),
+                })
             })
         } else {
             None
@@ -1212,9 +1920,32 @@ impl Ui {
             Mode::Inventory(InvMode::Equip) => {
                 nc::waddstr(window, &format!("Equip/use what?\n"));
             }
+            Mode::Inventory(InvMode::Throw) => {
+                nc::waddstr(window, &format!("Throw what?\n"));
+            }
+            Mode::Target(TargetMode::Throw(_)) => {
+                nc::waddstr(window, &format!("Throw where?\n"));
+            }
+            Mode::Spells => {
+                nc::waddstr(window, &format!("Cast what?\n"));
+            }
+            Mode::Pile(_) => {
+                nc::waddstr(window, &format!("Pick up what?\n"));
+            }
+            Mode::Target(TargetMode::Spell(spell)) => {
+                nc::waddstr(window, &format!("Cast {} where?\n", spell.description()));
+            }
             _ => {}
         }
 
+        if let Some(ref replay) = self.replay {
+            nc::waddstr(window, if replay.is_paused() {
+                "Replay paused.\n"
+            } else {
+                "Replaying...\n"
+            });
+        }
+
         let cury = nc::getcury(window);
         let maxy = nc::getmaxy(window) - 1;
         if cury < maxy {
@@ -1250,10 +1981,83 @@ impl Ui {
                     "Join chat: https://gitter.im/dpc/rhex\n");
         nc::waddstr(window,
                     "Project page: https://github.com/dpc/rhex\n\n");
+        nc::waddstr(window,
+                    &format!("Find the orb of hexes on level {} and bring it back to the surface.\n\n",
+                             game::consts::FINAL_LEVEL));
         nc::waddstr(window,
                     &format!("You can press {} in the game for offline help.\n\n",
                              KEY_HELP as u8 as char));
-        nc::waddstr(window, "Press anything to start.");
+        nc::waddstr(window, "Press h to see the high scores, anything else to start.");
+        nc::wnoutrefresh(window);
+    }
+
+    fn draw_game_over(&mut self) {
+        self.windows.full.clear(&self.calloc);
+        let window = self.windows.full.window;
+        let player = self.player();
+        let record = self.engine.record();
+
+        nc::wmove(window, 0, 0);
+
+        if record.won {
+            nc::waddstr(window, "You have escaped the dungeon with the orb!\n\n");
+        } else {
+            nc::waddstr(window, "You have died.\n\n");
+        }
+        nc::waddstr(window,
+                    &format!("The {} {} on level {} in turn {}.\n",
+                             player.description(),
+                             record.end_description(),
+                             self.current_location().level,
+                             self.engine.turn()));
+        nc::waddstr(window,
+                    &format!("Killed {} monsters. Score: {}.\n\n", record.kills, record.score()));
+
+        if let Some(rank) = self.game_over_rank {
+            nc::waddstr(window, &format!("Your run is #{} in the high scores.\n", rank + 1));
+        }
+        if let Some(ref path) = self.game_over_morgue {
+            nc::waddstr(window, &format!("Morgue file written to {}.\n", path));
+        }
+
+        nc::waddstr(window, "\nPress anything to continue.");
+        nc::wnoutrefresh(window);
+    }
+
+    fn draw_high_scores(&mut self) {
+        self.windows.full.clear(&self.calloc);
+        let window = self.windows.full.window;
+
+        nc::wmove(window, 0, 0);
+
+        nc::waddstr(window, "High scores\n\n");
+        if self.high_scores.entries.is_empty() {
+            nc::waddstr(window, "None yet.\n");
+        }
+
+        for (i, entry) in self.high_scores.entries.iter().enumerate() {
+            if Some(i) == self.game_over_rank {
+                nc::wattron(window, self.green_color);
+            } else {
+                nc::wattron(window, self.text_color);
+            }
+            nc::waddstr(window,
+                        &format!("{:>2}. {:>6}  {}, {}, reached level {} in turn {}",
+                                 i + 1,
+                                 entry.score,
+                                 entry.race.description(),
+                                 entry.end_description(),
+                                 entry.depth,
+                                 entry.turn));
+            if !entry.artifacts.is_empty() {
+                let names: Vec<_> = entry.artifacts.iter().map(|a| a.name()).collect();
+                nc::waddstr(window, &format!(", found {}", names.join(", ")));
+            }
+            nc::waddstr(window, "\n");
+        }
+
+        nc::wattron(window, self.text_color);
+        nc::waddstr(window, "\nPress anything to continue.");
         nc::wnoutrefresh(window);
     }
 
@@ -1271,6 +2075,41 @@ impl Ui {
         nc::wnoutrefresh(window);
     }
 
+    fn draw_level_up(&mut self) {
+        self.windows.full.clear(&self.calloc);
+        let window = self.windows.full.window;
+        let player = self.player();
+        let stats = &player.base_stats;
+
+        nc::wmove(window, 0, 0);
+
+        nc::waddstr(window,
+                    &format!("Level {} ({}/{} XP)\n\n",
+                             player.xp_level,
+                             player.xp,
+                             actor::xp_for_level(player.xp_level + 1)));
+        nc::waddstr(window,
+                    &format!("Attribute points to spend: {}\n\n", player.attr_points));
+        for (i, &attr) in Attribute::all().iter().enumerate() {
+            let val = match attr {
+                Attribute::Str => stats.str_,
+                Attribute::Int => stats.int,
+                Attribute::Dex => stats.dex,
+            };
+            nc::waddstr(window,
+                        &format!("{}) {}: {}\n", (b'a' + i as u8) as char, attr.description(), val));
+        }
+
+        nc::waddstr(window, "\nSkills:\n");
+        for skill in Skill::all().iter() {
+            nc::waddstr(window,
+                        &format!(" {:<8} {}\n", skill.description(), player.skills.level(*skill)));
+        }
+        nc::waddstr(window, "\n[ESC] to close...\n");
+
+        nc::wnoutrefresh(window);
+    }
+
     fn draw_help(&mut self) {
         self.windows.full.clear(&self.calloc);
         let window = self.windows.full.window;
@@ -1288,15 +2127,36 @@ impl Ui {
         nc::waddstr(window, "Charge: c\n");
         nc::waddstr(window, "Wait: .\n");
         nc::waddstr(window, "Descend: >\n");
+        nc::waddstr(window, "Ascend: <\n");
         nc::waddstr(window, "Autoexplore: o\n");
-        nc::waddstr(window, "Go to: G (only '>' follow-up implemented)\n");
+        nc::waddstr(window, "Go to: G (followed by '>' or '<')\n");
         nc::waddstr(window, "Examine: x\n");
-        nc::waddstr(window, "Pick item in front: ,\n");
+        nc::waddstr(window, "Pick up item in front: ,\n");
         nc::waddstr(window, "Look at Inventory: I\n");
         nc::waddstr(window, "Equip/Use: E\n");
         nc::waddstr(window, "Drop: D\n");
-        nc::waddstr(window, "Ranged/Throw: f (not fully working)\n");
+        nc::waddstr(window, "Shoot: f\n");
+        nc::waddstr(window, "Throw: t\n");
+        nc::waddstr(window, "Attributes and skills: A\n");
+        nc::waddstr(window, "Shove: p\n");
+        nc::waddstr(window, "Cast a spell: z\n");
         nc::waddstr(window, "Quit: ESC/q\n");
+        if self.replay.is_some() {
+            nc::waddstr(window, "\nReplay: [space] pause, [.] step, [+/-] speed, [f] fast forward\n");
+        }
+        nc::wnoutrefresh(window);
+    }
+
+    fn draw_resume(&mut self) {
+        self.windows.full.clear(&self.calloc);
+        let window = self.windows.full.window;
+
+        let (max_y, max_x) = Ui::screen_size();
+        let text = "A saved game was found. Continue it? [y/n]";
+
+        nc::wmove(window, max_y / 2, (max_x - text.chars().count() as i32) / 2);
+
+        nc::waddstr(window, text);
         nc::wnoutrefresh(window);
     }
 
@@ -1305,7 +2165,12 @@ impl Ui {
         let window = self.windows.full.window;
 
         let (max_y, max_x) = Ui::screen_size();
-        let text = "Quit. Are you sure?";
+        let text = if self.spawned && self.replay.is_none() && !self.player().is_dead() &&
+                      !self.engine.is_won() {
+            "Save and quit. Are you sure?"
+        } else {
+            "Quit. Are you sure?"
+        };
 
         nc::wmove(window, max_y / 2, (max_x - text.chars().count() as i32) / 2);
 
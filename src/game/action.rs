@@ -1,16 +1,36 @@
 use hex2d::{Angle, Coordinate};
 
-#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+use super::spell::Spell;
+
+/// Everything an actor can do in its tick
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
 pub enum Action {
     Wait,
+    /// Turn in place
     Turn(Angle),
+    /// Step (or attack) in a direction relative to the facing
     Move(Angle),
+    /// Run two hexes forward (or attack)
     Charge,
+    /// Move around the hex in front, keeping facing it
     Spin(Angle),
+    /// Equip, unequip or use an item with a given letter
     Equip(char),
+    /// Drop an item with a given letter
     Drop_(char),
+    /// Shoot at a given coordinate
     Ranged(Coordinate),
-    Pick,
+    /// Throw a backpack item with a given letter at a given coordinate
+    Throw(char, Coordinate),
+    /// Pick up an item with a given index from the pile in front
+    Pick(usize),
+    /// Push the actor in front one hex away
+    Shove,
+    /// Take the stairs down
     Descend,
+    /// Take the stairs up
+    Ascend,
+    /// Cast a spell, at a given coordinate if it needs a target
+    Cast(Spell, Option<Coordinate>),
 }
 
@@ -1,6 +1,6 @@
 use std::collections::{HashMap, HashSet};
 use std::ops::{Add, Sub};
-use std::cmp;
+use std::{cmp, mem};
 
 use hex2d::{Coordinate, Angle, Position, Direction, Left, Right, Forward};
 use hex2dext::algo;
@@ -8,13 +8,18 @@ use hex2dext::algo;
 use game::{self, Action, tile};
 use game::tile::Feature;
 use util;
-use super::item::Item;
+use super::artifact::Special;
+use super::item::{Item, Pattern};
+use super::event::{Attack, Event, Obstacle};
+use super::skill::{self, Skill, Skills};
+use super::spell::{self, Spell};
+use super::status::{Status, Statuses};
 
 use self::Race::*;
 use std::iter::Iterator;
 
-use rand;
 use rand::Rng;
+use rand::prelude::SliceRandom;
 
 use super::consts::*;
 use super::{Visibility, NoiseMap};
@@ -23,7 +28,7 @@ use super::{Location, Noise};
 
 pub type Id = u32;
 
-#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
 pub enum Race {
     Human,
     Elf,
@@ -47,7 +52,7 @@ impl Race {
     }
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
 pub struct Stats {
     pub int: i32,
     pub dex: i32,
@@ -60,14 +65,20 @@ pub struct Stats {
     pub infravision: i32,
     pub vision: i32,
     pub regeneration: u32,
+    /// Energy gained every turn
+    pub speed: i32,
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
 pub struct EffectiveStats {
     pub base: Stats,
     pub melee_dmg: i32,
     pub melee_acc: i32,
     pub melee_str_req: i32,
+    pub ranged_dmg: i32,
+    pub ranged_acc: i32,
+    /// How far can shoot; 0 without a ranged weapon
+    pub ranged_range: i32,
     pub light_emision: u32,
 }
 
@@ -104,6 +115,7 @@ impl Default for Stats {
             infravision: 0,
             vision: 0,
             regeneration: 0,
+            speed: 0,
         }
     }
 }
@@ -115,6 +127,9 @@ impl Default for EffectiveStats {
             melee_dmg: 0,
             melee_acc: 0,
             melee_str_req: 0,
+            ranged_dmg: 0,
+            ranged_acc: 0,
+            ranged_range: 0,
             light_emision: 0,
         }
     }
@@ -136,6 +151,7 @@ impl Add for Stats {
             infravision: self.infravision + s.infravision,
             vision: self.vision + s.vision,
             regeneration: self.regeneration + s.regeneration,
+            speed: self.speed + s.speed,
         }
     }
 }
@@ -149,6 +165,9 @@ impl Add for EffectiveStats {
             melee_dmg: self.melee_dmg + s.melee_dmg,
             melee_acc: self.melee_acc + s.melee_acc,
             melee_str_req: self.melee_str_req + s.melee_str_req,
+            ranged_dmg: self.ranged_dmg + s.ranged_dmg,
+            ranged_acc: self.ranged_acc + s.ranged_acc,
+            ranged_range: self.ranged_range + s.ranged_range,
             light_emision: self.light_emision + s.light_emision,
         }
     }
@@ -169,7 +188,8 @@ impl Sub for Stats {
             ev: self.ev - s.ev,
             infravision: self.infravision - s.infravision,
             vision: self.vision - s.vision,
-            regeneration: self.regeneration - self.regeneration,
+            regeneration: self.regeneration - s.regeneration,
+            speed: self.speed - s.speed,
         }
     }
 }
@@ -183,12 +203,90 @@ impl Sub for EffectiveStats {
             melee_dmg: self.melee_dmg - s.melee_dmg,
             melee_acc: self.melee_acc - s.melee_acc,
             melee_str_req: self.melee_str_req - s.melee_str_req,
+            ranged_dmg: self.ranged_dmg - s.ranged_dmg,
+            ranged_acc: self.ranged_acc - s.ranged_acc,
+            ranged_range: self.ranged_range - s.ranged_range,
             light_emision: self.light_emision - s.light_emision,
         }
     }
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, Ord, PartialOrd)]
+/// Side of an actor that an attack comes from
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub enum Arc {
+    Front,
+    LeftFlank,
+    RightFlank,
+    /// Including the rear diagonals
+    Rear,
+}
+
+impl Arc {
+    /// Arc hit by an attack going in `dir` at a target facing `facing`
+    pub fn of_attack(dir: Direction, facing: Direction) -> Arc {
+        match dir - facing {
+            Angle::Back => Arc::Front,
+            Angle::RightBack => Arc::LeftFlank,
+            Angle::LeftBack => Arc::RightFlank,
+            Angle::Forward | Angle::Left | Angle::Right => Arc::Rear,
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match *self {
+            Arc::Front => "front",
+            Arc::LeftFlank => "left flank",
+            Arc::RightFlank => "right flank",
+            Arc::Rear => "rear",
+        }
+    }
+
+    /// Evasion modifier of the target
+    pub fn ev_mod(&self) -> i32 {
+        match *self {
+            Arc::Front => 2,
+            Arc::LeftFlank | Arc::RightFlank => 0,
+            Arc::Rear => -2,
+        }
+    }
+
+    /// Can a shield held in the left hand block it?
+    pub fn shieldable(&self) -> bool {
+        match *self {
+            Arc::Front | Arc::LeftFlank => true,
+            Arc::RightFlank | Arc::Rear => false,
+        }
+    }
+}
+
+/// Attribute that can be raised with the points gained on level-up
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub enum Attribute {
+    Str,
+    Int,
+    Dex,
+}
+
+impl Attribute {
+    pub fn all() -> [Attribute; 3] {
+        [Attribute::Str, Attribute::Int, Attribute::Dex]
+    }
+
+    pub fn description(&self) -> &'static str {
+        match *self {
+            Attribute::Str => "strength",
+            Attribute::Int => "intelligence",
+            Attribute::Dex => "dexterity",
+        }
+    }
+}
+
+/// Total XP needed to reach a given character level
+pub fn xp_for_level(level: u32) -> u32 {
+    XP_BASE * level * (level - 1) / 2
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, Ord, PartialOrd, Serialize, Deserialize)]
 pub enum Slot {
     Head,
     Feet,
@@ -199,22 +297,20 @@ pub enum Slot {
     Quick,
 }
 
-#[derive(Clone, Eq, PartialEq, Debug)]
-pub struct AttackResult {
-    pub success: bool,
-    pub dmg: i32,
-    pub who: String,
-    pub behind: bool,
-}
-
-#[derive(Clone, Debug)]
+/// Player character or a monster
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Actor {
+    /// Assigned by the `Location` the actor is in
+    pub id: Id,
     pub hp: i32,
     pub mp: i32,
     pub sp: i32,
     pub saved_hp: i32,
     pub saved_mp: i32,
     pub saved_sp: i32,
+    /// Who hurt it last; credited with the kill, even if it dies of its
+    /// wounds later
+    last_harmer: Option<Id>,
 
     pub player: bool,
     pub pre_pos: Option<Position>,
@@ -222,6 +318,7 @@ pub struct Actor {
     pub pos: Position,
     pub acted: bool,
     descended: bool,
+    ascended: bool,
     opened_door: bool,
 
     pub race: Race,
@@ -253,14 +350,29 @@ pub struct Actor {
     pub heard: NoiseMap,
     pub noise_emision: i32,
 
-    pub action_cd: i32,
+    /// Can act when positive; see `consts::ACTION_COST`
+    pub energy: i32,
+
+    /// Total experience gathered
+    pub xp: u32,
+    /// Character level, starting at 1
+    pub xp_level: u32,
+    /// Not yet spent on attributes
+    pub attr_points: u32,
+
+    pub statuses: Statuses,
+    pub skills: Skills,
+
+    /// Known spells, in the order they were learned
+    pub spells: Vec<Spell>,
 
     pub items_letters: HashSet<char>,
     pub items_equipped: HashMap<Slot, (char, Box<Item>)>,
     pub items_backpack: HashMap<char, Box<Item>>,
 
-    pub was_attacked_by: Vec<AttackResult>,
-    pub did_attack: Vec<AttackResult>,
+    /// Events not yet collected by the `Location`
+    #[serde(skip)]
+    events: Vec<Event>,
 }
 
 impl Actor {
@@ -268,7 +380,9 @@ impl Actor {
         let stats = Stats::new(race);
 
         Actor {
+            id: 0,
             race: race,
+            last_harmer: None,
             player: false,
             pos: pos,
             pre_pos: None,
@@ -288,9 +402,14 @@ impl Actor {
             items_backpack: Default::default(),
             items_equipped: Default::default(),
             items_letters: Default::default(),
-            action_cd: 0,
-            was_attacked_by: Vec::new(),
-            did_attack: Vec::new(),
+            energy: 0,
+            xp: 0,
+            xp_level: 1,
+            attr_points: 0,
+            statuses: Default::default(),
+            skills: skill::starting(race),
+            spells: spell::starting(race),
+            events: Vec::new(),
             hp: stats.max_hp,
             mp: stats.max_mp,
             sp: stats.max_sp,
@@ -300,6 +419,7 @@ impl Actor {
             acted: false,
             opened_door: false,
             descended: false,
+            ascended: false,
         }
     }
 
@@ -338,9 +458,13 @@ impl Actor {
         let pos = self.pos;
         match action {
             Action::Wait |
-            Action::Pick |
+            Action::Pick(_) |
             Action::Equip(_) |
             Action::Descend |
+            Action::Ascend |
+            Action::Cast(..) |
+            Action::Throw(..) |
+            Action::Shove |
             Action::Ranged(_) |
             Action::Drop_(_) => vec![pos],
             Action::Turn(a) => vec![pos + a],
@@ -368,35 +492,122 @@ impl Actor {
         self.sp = cmp::max(0, self.sp - self.melee_sp_cost());
     }
 
+    /// Shoot a projectile towards `target_coord`
     pub fn attack_ranged(&mut self,
                          loc : &mut Location,
-                         target_coord : Coordinate) {
+                         target_coord : Coordinate,
+                         rng : &mut game::Rng) {
 
-        let target_id = match loc.actors_coord_to_id.get(&target_coord) {
+        let ammo = match self.take_ammo() {
             None => return,
-            Some(id) => id
+            Some(ammo) => ammo,
         };
 
-        let mut target= match loc.actors_byid.remove(&target_id) {
+        let range = self.stats.ranged_range;
+        let (landed, hit) = self.projectile_flight(loc, target_coord, range);
+
+        self.noise_makes(3);
+        self.practise(Skill::Bows);
+
+        if let Some((target_id, dir, dist)) = hit {
+            if let Some(mut target) = loc.actors_byid.remove(&target_id) {
+                let acc = self.stats.ranged_acc - dist / RANGED_ACC_FALLOFF;
+                let dmg = self.stats.ranged_dmg;
+                self.projectile_hits(&mut target, dir, acc, dmg, rng);
+                loc.actors_byid.insert(target_id, target);
+            }
+        }
+
+        loc.at_mut(landed).drop_item(ammo);
+    }
+
+    /// Throw a backpack item towards `target_coord`
+    pub fn throw(&mut self,
+                 ch: char,
+                 loc: &mut Location,
+                 target_coord: Coordinate,
+                 rng: &mut game::Rng) {
+        let item = match self.take_from_backpack(ch) {
             None => return,
-            Some(target) => target,
+            Some(item) => item,
         };
-        let mut acc = self.stats.melee_acc;
-        let mut dmg = self.stats.melee_dmg;
 
-        let (ac, ev) = (target.stats.base.ac, target.stats.base.ev);
+        let (landed, hit) = self.projectile_flight(loc, target_coord, THROW_RANGE);
 
-        let from_behind = match self.pos.dir - target.pos.dir {
-            Angle::Forward | Angle::Left | Angle::Right => true,
-            _ => false,
-        };
+        let id = self.id;
+        self.emit(Event::ItemThrown {
+            id: id,
+            coord: landed,
+            item: (*item).clone(),
+        });
+        self.noise_makes(3);
 
-        if from_behind {
+        if let Some((target_id, dir, dist)) = hit {
+            if let Some(mut target) = loc.actors_byid.remove(&target_id) {
+                if item.shatters() {
+                    item.use_(&mut target);
+                } else {
+                    let acc = self.stats.base.dex + item.thrown_acc() - dist / RANGED_ACC_FALLOFF;
+                    let dmg = item.thrown_dmg();
+                    self.projectile_hits(&mut target, dir, acc, dmg, rng);
+                }
+                loc.actors_byid.insert(target_id, target);
+            }
+        }
+
+        if !item.shatters() {
+            loc.at_mut(landed).drop_item(item);
+        }
+    }
+
+    /// Follow the line towards `target_coord`, up to `range` hexes
+    ///
+    /// The projectile stops at the first actor or obstacle. Returns where
+    /// it lands and, if it hit someone, their id, the direction it came
+    /// from and the distance.
+    fn projectile_flight(&self,
+                         loc: &Location,
+                         target_coord: Coordinate,
+                         range: i32)
+                         -> (Coordinate, Option<(Id, Option<Direction>, i32)>) {
+        let origin = self.coord();
+        let mut landed = origin;
+
+        for (i, coord) in origin.line_to_iter(target_coord)
+                                .filter(|&c| c != origin)
+                                .enumerate() {
+            // flies over water, but not through walls, statues or closed doors
+            if i as i32 >= range || loc.at(coord).tile().opaqueness() > 1 {
+                break;
+            }
+
+            let prev = landed;
+            landed = coord;
+
+            if let Some(&target_id) = loc.actors_coord_to_id.get(&coord) {
+                return (landed, Some((target_id, prev.direction_to_cw(coord), i as i32 + 1)));
+            }
+        }
+
+        (landed, None)
+    }
+
+    fn projectile_hits(&mut self,
+                       target: &mut Actor,
+                       dir: Option<Direction>,
+                       mut acc: i32,
+                       mut dmg: i32,
+                       rng: &mut game::Rng) {
+        let arc = dir.map_or(Arc::Front, |dir| Arc::of_attack(dir, target.pos.dir));
+        let (ac, ev, shielded) = target.defense(arc);
+
+        if arc == Arc::Rear {
             acc *= 2;
             dmg *= 2;
         }
 
-        if !self.can_attack_sp() {
+        let exhausted = !self.can_attack_sp();
+        if exhausted {
             acc /= 2;
             dmg /= 2;
             self.sp = 0;
@@ -404,41 +615,56 @@ impl Actor {
             self.substract_melee_sp_cost();
         }
 
-        let success = util::roll(acc, ev);
+        let success = util::roll(rng, acc, ev);
 
-        let rand_ac = cmp::max(rand::thread_rng().gen_range(0..(ac + 1)),
-                rand::thread_rng().gen_range(0..(ac + 1)));
+        let rand_ac = cmp::max(rng.gen_range(0..(ac + 1)),
+                rng.gen_range(0..(ac + 1)));
 
-        let dmg = cmp::max(0, dmg - rand_ac);
+        let attack = Attack {
+            attacker: self.id,
+            target: target.id,
+            ranged: true,
+            arc: arc,
+            shielded: shielded,
+            exhausted: exhausted,
+            acc: acc,
+            ev: ev,
+            success: success,
+            dmg: dmg,
+            absorbed: cmp::max(0, cmp::min(dmg, rand_ac)),
+        };
+        self.emit(Event::Attacked(attack));
+        target.practise_defense(success);
 
         if success {
-            target.hp -= dmg;
+            target.hp -= attack.dmg_dealt();
+            target.harmed_by(self.id);
             target.noise_makes(7);
         }
+    }
 
-        target.was_attacked_by.push(AttackResult {
-            success: success,
-            dmg: dmg,
-            who: self.description(),
-            behind: from_behind,
-        });
+    /// Take out a piece of ammunition, if there's any
+    fn take_ammo(&mut self) -> Option<Box<Item>> {
+        let ch = self.items_backpack
+                     .iter()
+                     .filter(|&(_, item)| item.is_ammo())
+                     .map(|(&ch, _)| ch)
+                     .min();
 
-        self.did_attack.push(AttackResult {
-            success: success,
-            dmg: dmg,
-            who: target.description(),
-            behind: from_behind,
-        });
+        ch.and_then(|ch| self.take_from_backpack(ch))
+    }
 
-        loc.actors_byid.insert(*target_id, target);
+    pub fn has_ammo(&self) -> bool {
+        self.items_backpack.values().any(|item| item.is_ammo())
     }
 
     pub fn try_attack_ranged(&mut self,
                          loc : &mut Location,
-                         target_coord : Coordinate) {
+                         target_coord : Coordinate,
+                         rng : &mut game::Rng) {
 
         if self.can_attack_ranged() {
-            self.attack_ranged(loc, target_coord);
+            self.attack_ranged(loc, target_coord, rng);
         }
     }
 
@@ -452,15 +678,237 @@ impl Actor {
                 self.acted = true;
             }
         }
+        self.energy -= self.action_cost(action);
+    }
+
+    /// Energy spent on performing `action`
+    ///
+    /// (Un)equipping and using items costs additional energy on top of it.
+    pub fn action_cost(&self, action: Action) -> i32 {
+        match action {
+            Action::Charge => CHARGE_COST,
+            Action::Spin(_) => SPIN_COST,
+            _ => ACTION_COST,
+        }
+    }
+
+    /// Regain energy at the beginning of a turn
+    ///
+    /// Unused energy is not stored for later.
+    pub fn gain_energy(&mut self) {
+        let speed = cmp::max(1, self.stats.base.speed);
+        self.energy = cmp::min(self.energy + speed, speed);
     }
 
     pub fn can_attack_at_angle(&self, angle : Angle) -> bool {
-        match angle {
-            Forward|Left|Right => true,
+        match (self.weapon_pattern(), angle) {
+            (Pattern::Reach, Forward) => true,
+            (Pattern::Reach, _) => false,
+            (_, Forward) | (_, Left) | (_, Right) => true,
             _ => false,
         }
     }
 
+    pub fn weapon_pattern(&self) -> Pattern {
+        self.equipped_in_slot(Slot::RHand).map_or(Pattern::Single, |item| item.pattern())
+    }
+
+    pub fn backstab_mult(&self) -> i32 {
+        self.equipped_in_slot(Slot::RHand).map_or(2, |item| item.backstab_mult())
+    }
+
+    pub fn is_hostile_to(&self, other: &Actor) -> bool {
+        self.is_player() != other.is_player()
+    }
+
+    /// Is `coord` in the front arc, where it can react to movement?
+    pub fn threatens(&self, coord: Coordinate) -> bool {
+        !self.is_dead() && !self.has_status(Status::Stunned) &&
+        [Left, Forward, Right].iter().any(|&angle| self.head_rel(angle) == coord)
+    }
+
+    /// Free attack at `target` leaving `from`
+    pub fn attack_of_opportunity(&mut self, from: Coordinate, target: &mut Actor, rng: &mut game::Rng) {
+        let dir = self.coord().direction_to_cw(from).unwrap_or(self.pos.dir);
+        let id = self.id;
+        self.emit(Event::OpportunityAttack {
+            attacker: id,
+            target: target.id,
+        });
+        self.attacks(dir, target, rng);
+    }
+
+    /// Armor and evasion against an attack hitting `arc`
+    ///
+    /// Also tells if the shield took part in the defense.
+    pub fn defense(&self, arc: Arc) -> (i32, i32, bool) {
+        let mut ac = self.stats.base.ac;
+        let mut ev = cmp::max(0, self.stats.base.ev + arc.ev_mod());
+
+        let shield = if arc.shieldable() {
+            self.equipped_in_slot(Slot::LHand).and_then(|item| item.shield())
+        } else {
+            None
+        };
+        if let Some((shield_ac, shield_ev)) = shield {
+            ac += shield_ac;
+            ev += shield_ev;
+        }
+
+        (ac, ev, shield.is_some())
+    }
+
+    pub fn ripostes(&self) -> bool {
+        self.equipped_in_slot(Slot::RHand).map_or(false, |item| item.ripostes())
+    }
+
+    /// Enemy two hexes ahead that a reach weapon hits instead of stepping forward
+    fn reach_target(&self, action: Action, loc: &Location) -> Option<Coordinate> {
+        if action != Action::Move(Forward) || self.weapon_pattern() != Pattern::Reach ||
+           !self.can_attack() {
+            return None;
+        }
+
+        let head = self.head();
+        let beyond = head + self.pos.dir;
+        if loc.actors_coord_to_id.contains_key(&head) || loc.at(head).tile().opaqueness() > 1 ||
+           !loc.actors_coord_to_id.contains_key(&beyond) {
+            return None;
+        }
+
+        Some(beyond)
+    }
+
+    /// Attack whoever stands at `coord`
+    ///
+    /// A missed attack from the front can be riposted. A hit with
+    /// `knockback` or a heavy weapon pushes the target along `dir`.
+    fn melee(&mut self,
+             dir: Direction,
+             coord: Coordinate,
+             knockback: bool,
+             loc: &mut Location,
+             rng: &mut game::Rng) {
+        let target_id = match loc.actors_coord_to_id.get(&coord) {
+            Some(&id) => id,
+            None => return,
+        };
+        let mut target = match loc.actors_byid.remove(&target_id) {
+            Some(target) => target,
+            None => return,
+        };
+
+        let attack = self.attacks(dir, &mut target, rng);
+        if !attack.success && attack.arc != Arc::Rear && !target.is_dead() && target.ripostes() {
+            target.attacks(dir + Angle::Back, self, rng);
+        }
+
+        if attack.success && !target.is_dead() && (knockback || self.wields_heavy()) {
+            target.knocked_back(dir, self.id, loc);
+        }
+
+        loc.actors_byid.insert(target_id, target);
+    }
+
+    pub fn wields_heavy(&self) -> bool {
+        self.equipped_in_slot(Slot::RHand).map_or(false, |item| item.is_heavy())
+    }
+
+    /// Try to push the actor in front away
+    fn shove(&mut self, loc: &mut Location, rng: &mut game::Rng) {
+        let head = self.head();
+        let target_id = match loc.actors_coord_to_id.get(&head) {
+            Some(&id) => id,
+            None => return,
+        };
+        let mut target = match loc.actors_byid.remove(&target_id) {
+            Some(target) => target,
+            None => return,
+        };
+
+        let success = util::roll(rng, self.stats.base.str_, target.stats.base.str_);
+        let id = self.id;
+        self.emit(Event::Shoved {
+            id: id,
+            target: target_id,
+            success: success,
+        });
+        self.noise_makes(4);
+
+        if success {
+            let dir = self.pos.dir;
+            target.knocked_back(dir, id, loc);
+        }
+
+        loc.actors_byid.insert(target_id, target);
+    }
+
+    /// Get pushed one hex along `dir` by actor `by`
+    ///
+    /// Walls and other actors stop it and hurt; water stuns, or drowns
+    /// the ones too exhausted to swim out. Must be called with the actor
+    /// taken out of `loc.actors_byid`.
+    pub fn knocked_back(&mut self, dir: Direction, by: Id, loc: &mut Location) {
+        let from = self.pos;
+        let to = from.coord + dir;
+        let (id, by_id) = (self.id, by);
+
+        let obstacle = if let Some(&other_id) = loc.actors_coord_to_id.get(&to) {
+            if let Some(other) = loc.actors_byid.get_mut(&other_id) {
+                other.collides(Obstacle::Actor(id), COLLISION_DMG);
+                other.harmed_by(by_id);
+            }
+            Some(Obstacle::Actor(other_id))
+        } else if loc.at(to).tile().type_ == tile::Water {
+            Some(Obstacle::Water)
+        } else if !loc.at(to).tile().is_passable() {
+            Some(Obstacle::Wall)
+        } else {
+            None
+        };
+
+        self.harmed_by(by_id);
+        self.emit(Event::Pushed {
+            id: id,
+            by: by_id,
+            from: from.coord,
+            to: if obstacle.is_none() { Some(to) } else { None },
+        });
+
+        match obstacle {
+            None => {
+                loc.actors_coord_to_id.remove(&from.coord);
+                let new_pos = Position {
+                    coord: to,
+                    dir: from.dir,
+                };
+                self.moved(loc, new_pos);
+                loc.actors_coord_to_id.insert(to, id);
+            }
+            Some(Obstacle::Water) => {
+                if self.sp == 0 {
+                    let hp = self.hp;
+                    self.collides(Obstacle::Water, hp);
+                } else {
+                    self.collides(Obstacle::Water, 0);
+                    self.add_status(Status::Stunned, WATER_STUN, 0);
+                }
+            }
+            Some(obstacle) => self.collides(obstacle, COLLISION_DMG),
+        }
+    }
+
+    fn collides(&mut self, obstacle: Obstacle, dmg: i32) {
+        let id = self.id;
+        self.emit(Event::Collided {
+            id: id,
+            obstacle: obstacle,
+            dmg: dmg,
+        });
+        self.hp -= dmg;
+        self.noise_makes(5);
+    }
+
     pub fn can_dig_at_angle(&self, angle : Angle) -> bool {
         match angle {
             Forward|Left|Right => true,
@@ -539,10 +987,30 @@ impl Actor {
     pub fn noise_makes(&mut self, noise: i32) {
         if self.noise_emision < noise {
             self.noise_emision = noise;
+            let (id, coord) = (self.id, self.coord());
+            self.emit(Event::NoiseMade {
+                id: id,
+                coord: coord,
+                loudness: noise,
+            });
         }
     }
 
-    pub fn open_door(&mut self, loc : &Location) {
+    fn emit(&mut self, event: Event) {
+        self.events.push(event);
+    }
+
+    /// Take the events this actor caused since the last call
+    pub fn take_events(&mut self) -> Vec<Event> {
+        mem::replace(&mut self.events, Vec::new())
+    }
+
+    pub fn open_door(&mut self, coord: Coordinate, loc : &Location) {
+        let id = self.id;
+        self.emit(Event::DoorOpened {
+            id: id,
+            coord: coord,
+        });
         self.opened_door = true;
         self.add_current_los_to_temporary_los(loc);
         self.noise_makes(3);
@@ -558,8 +1026,6 @@ impl Actor {
         self.opened_door = false;
         self.pre_pos = Some(self.pos);
         self.pre_head = Some(self.head());
-        self.did_attack = Vec::new();
-        self.was_attacked_by = Vec::new();
         self.temporary_los = Default::default();
 
         self.discovered = Default::default();
@@ -570,30 +1036,114 @@ impl Actor {
 
         self.acted = false;
         self.descended = false;
+        self.ascended = false;
 
-        if self.action_cd > 0 {
-            self.action_cd -= 1;
-        }
         if self.can_perform_action() {
             self.save_stats();
         }
     }
 
     pub fn post_own_tick(&mut self, loc: &Location) {
+        if !self.is_dead() {
+            if self.pre_pos != Some(self.pos) || self.opened_door {
+                self.postprocess_visibile(loc);
+            }
+            self.tick_statuses();
+            if self.is_player() {
+                self.wear_equipped();
+            }
+        }
+    }
+
+    /// Equipped items get identified after being worn for a while
+    fn wear_equipped(&mut self) {
+        let mut slots: Vec<_> = self.items_equipped.keys().cloned().collect();
+        slots.sort();
+
+        for slot in slots {
+            let identified = {
+                let item = &mut self.items_equipped.get_mut(&slot).unwrap().1;
+                if item.wear() {
+                    Some((**item).clone())
+                } else {
+                    None
+                }
+            };
+
+            if let Some(item) = identified {
+                let id = self.id;
+                self.emit(Event::ItemIdentified {
+                    id: id,
+                    item: item,
+                });
+            }
+        }
+    }
+
+    fn tick_statuses(&mut self) {
+        self.hp -= self.statuses.damage();
+
+        let id = self.id;
+        for status in self.statuses.tick() {
+            self.emit(Event::StatusEnded {
+                id: id,
+                status: status,
+            });
+        }
+    }
+
+    /// Put the actor under `status` for `duration` own ticks
+    pub fn add_status(&mut self, status: Status, duration: u32, strength: i32) {
+        if self.wards(status) {
+            return;
+        }
+
+        let id = self.id;
+        if let Some(cancelled) = self.statuses.add(status, duration, strength) {
+            self.emit(Event::StatusEnded {
+                id: id,
+                status: cancelled,
+            });
+        }
+        self.emit(Event::StatusGained {
+            id: id,
+            status: status,
+        });
+        self.recalculate_stats();
+    }
+
+    pub fn has_status(&self, status: Status) -> bool {
+        self.statuses.has(status)
+    }
+
+    /// A confused actor sometimes stumbles in a random direction
+    fn confuse_action(&self, action: Action, rng: &mut game::Rng) -> Action {
+        match action {
+            Action::Move(_) | Action::Charge if self.has_status(Status::Confused) &&
+                                                rng.gen_bool(CONFUSION_CHANCE) => {
+                Action::Move(*Angle::all().choose(rng).unwrap())
+            }
+            _ => action,
+        }
+    }
+
+    /// Recover some SP, MP and HP; once a turn, no matter how many actions
+    pub fn regenerate(&mut self, rng: &mut game::Rng) {
         if !self.is_dead() {
             if self.sp < self.stats.base.max_sp &&
-                rand::thread_rng().gen_bool(1.0 / 10.0) {
+                rng.gen_bool(1.0 / 10.0) {
                     self.sp += 1
                 }
 
-            if self.hp < self.stats.base.max_hp && rand::thread_rng()
+            if self.mp < self.stats.base.max_mp &&
+                rng.gen_range(0..30) < self.stats.base.int {
+                    self.mp += 1
+                }
+
+            if self.hp < self.stats.base.max_hp && rng
                 .gen_range(0..50) < self.stats.base.regeneration {
                     self.hp += 1
             }
-
-            if self.pre_pos != Some(self.pos) || self.opened_door {
-                self.postprocess_visibile(loc);
-            }
         }
     }
 
@@ -602,8 +1152,6 @@ impl Actor {
     }
 
     pub fn pre_spawn(&mut self, loc: &Location) {
-        self.known = Default::default();
-        self.known_areas = Default::default();
         self.recalculate_stats();
         let pos = self.pos;
         self.moved(loc, pos);
@@ -614,16 +1162,53 @@ impl Actor {
         self.postprocess_visibile(loc);
     }
 
+    /// Put an item into the backpack, on a matching stack if there's one
+    ///
+    /// Gives the item back if there's no room for it.
     pub fn pick_item(&mut self, item: Box<Item>) -> Option<Box<Item>> {
-        for ch in ('a' as u8..'z' as u8).chain('A' as u8..'Z' as u8) {
-            let ch = ch as char;
-            if !self.item_letter_taken(ch) {
-                assert!(!self.items_backpack.contains_key(&ch));
+        if let Some(stack) = self.items_backpack.values_mut().find(|i| i.stacks_with(&item)) {
+            stack.merge(item);
+            return None;
+        }
+
+        match self.free_letter() {
+            Some(ch) => {
+                self.items_backpack.insert(ch, item);
+                None
+            }
+            None => Some(item),
+        }
+    }
+
+    fn free_letter(&self) -> Option<char> {
+        ('a' as u8..='z' as u8)
+            .chain('A' as u8..='Z' as u8)
+            .map(|ch| ch as char)
+            .find(|&ch| !self.item_letter_taken(ch))
+    }
+
+    pub fn has_room_for(&self, item: &Item) -> bool {
+        self.items_backpack.values().any(|i| i.stacks_with(item)) || self.free_letter().is_some()
+    }
+
+    /// Take a single item out of the backpack, splitting its stack
+    fn take_from_backpack(&mut self, ch: char) -> Option<Box<Item>> {
+        let split = match self.items_backpack.get_mut(&ch) {
+            Some(stack) if stack.quantity() > 1 => Some(stack.split_one()),
+            _ => None,
+        };
+
+        split.or_else(|| self.items_backpack.remove(&ch))
+    }
+
+    /// Put an item taken out with `take_from_backpack` back on its letter
+    fn return_to_backpack(&mut self, ch: char, item: Box<Item>) {
+        match self.items_backpack.get_mut(&ch) {
+            Some(stack) => stack.merge(item),
+            None => {
                 self.items_backpack.insert(ch, item);
-                return None;
             }
         }
-        Some(item)
     }
 
     pub fn item_letter_taken(&self, ch: char) -> bool {
@@ -642,12 +1227,18 @@ impl Actor {
 
     pub fn equip_switch(&mut self, ch: char) {
         if self.items_backpack.contains_key(&ch) {
-            if let Some(item) = self.items_backpack.remove(&ch) {
+            if let Some(item) = self.take_from_backpack(ch) {
                 if item.is_usable() {
-                    if !item.use_(self) {
-                        self.items_backpack.insert(ch, item);
+                    if item.use_(self) {
+                        let id = self.id;
+                        self.emit(Event::ItemUsed {
+                            id: id,
+                            item: *item,
+                        });
+                    } else {
+                        self.return_to_backpack(ch, item);
                     }
-                    self.action_cd += 2;
+                    self.energy -= USE_COST;
                 } else {
                     self.equip(item, ch);
                 }
@@ -658,23 +1249,46 @@ impl Actor {
         }
     }
 
+    /// Take off and drop an item, or just one item of a stack
     pub fn equip_drop(&mut self, ch: char) -> Option<Box<Item>> {
         self.unequip(ch);
-        self.items_backpack.remove(&ch)
+        self.take_from_backpack(ch)
     }
 
-    pub fn equip(&mut self, item: Box<Item>, ch: char) {
+    pub fn equip(&mut self, mut item: Box<Item>, ch: char) {
         if let Some(slot) = item.slot() {
+            if !self.can_unequip_slot(slot) {
+                self.return_to_backpack(ch, item);
+                return;
+            }
             self.unequip_slot(slot);
+            // the curse reveals itself right away
+            if item.is_cursed() {
+                item.identify();
+            }
             self.mod_stats = self.mod_stats + item.stats();
+            let id = self.id;
+            self.emit(Event::ItemEquipped {
+                id: id,
+                slot: slot,
+                item: (*item).clone(),
+            });
             self.items_equipped.insert(slot, (ch, item));
-            self.action_cd += if slot == Slot::Body {
-                4
+            self.energy -= if slot == Slot::Body {
+                EQUIP_BODY_COST
             } else {
-                2
+                EQUIP_COST
             }
         } else {
-            self.items_backpack.insert(ch, item);
+            self.return_to_backpack(ch, item);
+        }
+    }
+
+    /// Is the item with a given letter worn, wearable or usable?
+    pub fn can_equip(&self, ch: char) -> bool {
+        match self.items_backpack.get(&ch) {
+            Some(item) => item.slot().is_some() || item.is_usable(),
+            None => self.item_letter_taken(ch),
         }
     }
 
@@ -691,14 +1305,57 @@ impl Actor {
                         &[pos.dir]);
     }
 
+    /// Is the slot free, or holding something that can be taken off?
+    pub fn can_unequip_slot(&self, slot: Slot) -> bool {
+        self.equipped_in_slot(slot).map_or(true, |item| !item.is_cursed())
+    }
+
+    /// Would equipping, unequipping or dropping the item with a given
+    /// letter require taking off a cursed one?
+    pub fn blocked_by_curse(&self, ch: char, equipping: bool) -> bool {
+        let equipped = self.items_equipped
+                           .iter()
+                           .find(|&(_, &(item_ch, _))| item_ch == ch)
+                           .map(|(&slot, _)| slot);
+
+        match equipped {
+            Some(slot) => !self.can_unequip_slot(slot),
+            None if equipping => {
+                self.items_backpack
+                    .get(&ch)
+                    .and_then(|item| item.slot())
+                    .map_or(false, |slot| !self.can_unequip_slot(slot))
+            }
+            None => false,
+        }
+    }
+
+    /// Lift the curses off everything equipped
+    pub fn uncurse_equipped(&mut self) {
+        for &mut (_, ref mut item) in self.items_equipped.values_mut() {
+            item.uncurse();
+        }
+    }
+
+    /// Cursed items stay on
     pub fn unequip_slot(&mut self, slot: Slot) {
+        if !self.can_unequip_slot(slot) {
+            return;
+        }
+
         if let Some((ch, item)) = self.items_equipped.remove(&slot) {
             self.mod_stats = self.mod_stats - item.stats();
+            let id = self.id;
+            self.emit(Event::ItemUnequipped {
+                id: id,
+                slot: slot,
+                item: (*item).clone(),
+            });
             self.items_backpack.insert(ch, item);
-            self.action_cd += if slot == Slot::Body {
-                4
+            self.energy -= if slot == Slot::Body {
+                EQUIP_BODY_COST
             } else {
-                2
+                EQUIP_COST
             }
         }
     }
@@ -722,29 +1379,63 @@ impl Actor {
         // Add attributes to derived stats
         self.stats.melee_dmg += self.stats.base.str_;
         self.stats.melee_acc += self.stats.base.dex;
+        self.stats.ranged_acc += self.stats.base.dex;
         self.stats.base.ac += self.stats.base.str_ / 2;
         self.stats.base.ev += self.stats.base.dex / 2;
         self.stats.base.max_sp += self.stats.base.str_ * 2;
         self.stats.base.max_mp += self.stats.base.int * 2;
+
+        let weapon = self.weapon_skill();
+        self.skills.apply(&mut self.stats, weapon);
+        self.statuses.apply(&mut self.stats);
+    }
+
+    /// Skill used by the wielded weapon; none when fighting bare-handed
+    pub fn weapon_skill(&self) -> Option<Skill> {
+        self.equipped_in_slot(Slot::RHand).and_then(|item| item.skill())
+    }
+
+    /// Count a use of `skill`; only the player learns from experience
+    fn practise(&mut self, skill: Skill) {
+        if !self.is_player() {
+            return;
+        }
+
+        if let Some(level) = self.skills.practise(skill) {
+            self.recalculate_stats();
+            let id = self.id;
+            self.emit(Event::SkillImproved {
+                id: id,
+                skill: skill,
+                level: level,
+            });
+        }
+    }
+
+    /// Being attacked teaches dodging, or wearing armor if it hits
+    fn practise_defense(&mut self, hit: bool) {
+        self.practise(if hit {
+            Skill::Armor
+        } else {
+            Skill::Dodging
+        });
     }
 
-    pub fn attacks(&mut self, dir: Direction, target: &mut Actor) {
+    pub fn attacks(&mut self, dir: Direction, target: &mut Actor, rng: &mut game::Rng) -> Attack {
         let mut acc = self.stats.melee_acc;
         let mut dmg = self.stats.melee_dmg;
 
-        let (ac, ev) = (target.stats.base.ac, target.stats.base.ev);
-
-        let from_behind = match dir - target.pos.dir {
-            Angle::Forward | Angle::Left | Angle::Right => true,
-            _ => false,
-        };
+        let arc = Arc::of_attack(dir, target.pos.dir);
+        let (ac, ev, shielded) = target.defense(arc);
 
-        if from_behind {
-            acc *= 2;
-            dmg *= 2;
+        if arc == Arc::Rear {
+            let mult = self.backstab_mult();
+            acc *= mult;
+            dmg *= mult;
         }
 
-        if !self.can_attack_sp() {
+        let exhausted = !self.can_attack_sp();
+        if exhausted {
             acc /= 2;
             dmg /= 2;
             self.sp = 0;
@@ -752,42 +1443,140 @@ impl Actor {
             self.sp -= self.melee_sp_cost();
         }
 
-        let success = util::roll(acc, ev);
-        let rand_ac = cmp::max(rand::thread_rng().gen_range(0..(ac + 1)),
-                               rand::thread_rng().gen_range(0..(ac + 1)));
-
-        let dmg = cmp::max(0, dmg - rand_ac);
+        let success = util::roll(rng, acc, ev);
+        let rand_ac = cmp::max(rng.gen_range(0..(ac + 1)),
+                               rng.gen_range(0..(ac + 1)));
+
+        let attack = Attack {
+            attacker: self.id,
+            target: target.id,
+            ranged: false,
+            arc: arc,
+            shielded: shielded,
+            exhausted: exhausted,
+            acc: acc,
+            ev: ev,
+            success: success,
+            dmg: dmg,
+            absorbed: cmp::max(0, cmp::min(dmg, rand_ac)),
+        };
+        self.emit(Event::Attacked(attack));
+        if let Some(skill) = self.weapon_skill() {
+            self.practise(skill);
+        }
+        target.practise_defense(success);
 
         if success {
-            target.hp -= dmg;
+            target.hp -= attack.dmg_dealt();
+            target.harmed_by(self.id);
             target.noise_makes(7);
+            self.artifact_hits(target, attack.dmg_dealt());
         }
 
-        target.was_attacked_by.push(AttackResult {
-            success: success,
-            dmg: dmg,
-            who: self.description(),
-            behind: from_behind,
-        });
+        attack
+    }
 
-        self.did_attack.push(AttackResult {
-            success: success,
-            dmg: dmg,
-            who: target.description(),
-            behind: from_behind,
-        });
+    /// Apply the special effect of a wielded artifact to a target just hit
+    fn artifact_hits(&mut self, target: &mut Actor, dmg: i32) {
+        match self.equipped_in_slot(Slot::RHand).and_then(|item| item.special()) {
+            Some(Special::OnHit { status, duration, strength }) => {
+                target.add_status(status, duration, strength)
+            }
+            Some(Special::Drain) => self.hp = cmp::min(self.stats.base.max_hp, self.hp + dmg / 2),
+            _ => {}
+        }
+    }
+
+    /// The actor to blame for this one's death
+    pub fn last_harmer(&self) -> Option<Id> {
+        self.last_harmer
+    }
+
+    fn harmed_by(&mut self, id: Id) {
+        if id != self.id {
+            self.last_harmer = Some(id);
+        }
+    }
+
+    /// Is any of the equipped artifacts protecting from `status`?
+    pub fn wards(&self, status: Status) -> bool {
+        self.items_equipped
+            .values()
+            .any(|&(_, ref item)| item.special() == Some(Special::Wards(status)))
     }
 
     pub fn discovered_stairs(&self, loc: &Location) -> bool {
         self.discovered.iter().any(|c| loc.at(*c).tile().feature == Some(Feature::Stairs))
     }
 
+    /// Can leave the dungeon and win the game?
+    pub fn carries_orb(&self) -> bool {
+        self.items_backpack.values().any(|item| item.is_orb())
+    }
+
+    /// XP awarded for killing this actor
+    pub fn xp_value(&self) -> u32 {
+        let stats = &self.stats.base;
+        cmp::max(1,
+                 stats.max_hp + 2 * (stats.str_ + stats.dex + stats.int) + 2 * stats.ac +
+                 stats.ev) as u32
+    }
+
+    /// XP still missing to the next character level
+    pub fn xp_to_next_level(&self) -> u32 {
+        xp_for_level(self.xp_level + 1) - self.xp
+    }
+
+    pub fn gain_xp(&mut self, xp: u32) {
+        self.xp += xp;
+        while self.xp >= xp_for_level(self.xp_level + 1) {
+            self.level_up();
+        }
+    }
+
+    fn level_up(&mut self) {
+        self.xp_level += 1;
+        self.attr_points += 1;
+
+        self.base_stats.max_hp += LEVEL_HP;
+        self.base_stats.max_sp += LEVEL_SP;
+        self.base_stats.max_mp += LEVEL_MP;
+        self.hp += LEVEL_HP;
+        self.sp += LEVEL_SP;
+        self.mp += LEVEL_MP;
+        self.recalculate_stats();
+
+        let (id, level) = (self.id, self.xp_level);
+        self.emit(Event::LevelUp {
+            id: id,
+            level: level,
+        });
+    }
+
+    pub fn can_raise_attribute(&self) -> bool {
+        self.attr_points > 0 && !self.is_dead()
+    }
+
+    /// Spend an attribute point
+    pub fn raise_attribute(&mut self, attr: Attribute) {
+        debug_assert!(self.can_raise_attribute());
+        self.attr_points -= 1;
+
+        match attr {
+            Attribute::Str => self.base_stats.str_ += 1,
+            Attribute::Int => self.base_stats.int += 1,
+            Attribute::Dex => self.base_stats.dex += 1,
+        }
+        self.recalculate_stats();
+    }
+
     pub fn set_player(&mut self) {
         self.player = true;
     }
 
     pub fn melee_sp_cost(&self) -> i32 {
-        cmp::max(0, self.stats.melee_str_req - self.stats.base.str_)
+        let skill = self.weapon_skill().map_or(0, |skill| self.skills.level(skill) as i32);
+        cmp::max(0, self.stats.melee_str_req - self.stats.base.str_ - skill / 2)
     }
 
     pub fn charge_sp_cost(&self) -> i32 {
@@ -805,7 +1594,7 @@ impl Actor {
     }
 
     pub fn can_act(&self) -> bool {
-        self.action_cd == 0 && !self.is_dead()
+        self.energy > 0 && !self.is_dead()
     }
 
     pub fn can_attack(&self) -> bool {
@@ -813,7 +1602,7 @@ impl Actor {
     }
 
     pub fn can_attack_ranged(&self) -> bool {
-       self.can_act() && self.is_holding_ranged_weapon()
+       self.can_act() && self.is_holding_ranged_weapon() && self.has_ammo()
     }
 
     pub fn is_holding_ranged_weapon(&self) -> bool {
@@ -827,7 +1616,15 @@ impl Actor {
     pub fn moved(&mut self, loc: &Location, new_pos: Position) {
         self.pos = new_pos;
         self.add_current_los_to_temporary_los(loc);
-        self.noise_makes(2);
+        self.noise_makes(cmp::max(0, 2 - self.skills.level(Skill::Stealth) as i32 / 2));
+
+        // sneaking only counts with someone to sneak past
+        let watched = loc.actors_byid
+                         .values()
+                         .any(|a| !a.is_dead() && self.is_hostile_to(a) && self.sees(a.coord()));
+        if watched {
+            self.practise(Skill::Stealth);
+        }
     }
 
     pub fn is_player(&self) -> bool {
@@ -839,7 +1636,7 @@ impl Actor {
     }
 
     pub fn can_perform_action(&self) -> bool {
-        !self.is_dead() && self.action_cd == 0
+        !self.is_dead() && self.energy > 0
     }
 
     pub fn description(&self) -> String {
@@ -854,11 +1651,31 @@ impl Actor {
         self.descended
     }
 
+    pub fn ascend(&mut self) {
+        self.ascended = true;
+    }
+
+    pub fn ascended(&self) -> bool {
+        self.ascended
+    }
+
     pub fn light_emision(&self) -> u32 {
         self.stats.light_emision
     }
 
-    pub fn act(&mut self, loc : &mut Location, action: Action) {
+    pub fn act(&mut self, loc : &mut Location, action: Action, rng: &mut game::Rng) {
+        if self.has_status(Status::Stunned) {
+            return;
+        }
+
+        let action = self.confuse_action(action, rng);
+
+        if let Some(coord) = self.reach_target(action, loc) {
+            let dir = self.pos.dir;
+            self.melee(dir, coord, false, loc, rng);
+            return;
+        }
+
         let new_pos = self.pos_after_action(action);
 
         for &new_pos in &new_pos {
@@ -867,13 +1684,21 @@ impl Actor {
             if old_pos == new_pos {
                 // no movement
                 match action {
-                    Action::Pick => {
+                    Action::Pick(i) => {
                         let head = self.head();
-                        let item = loc.at_mut(head).pick_item();
+                        let item = loc.at_mut(head).pick_item(i);
 
                         if let Some(item) = item {
+                            let picked = (*item).clone();
                             if let Some(item) = self.pick_item(item) {
                                 loc.at_mut(head).drop_item(item);
+                            } else {
+                                let id = self.id;
+                                self.emit(Event::ItemPicked {
+                                    id: id,
+                                    coord: head,
+                                    item: picked,
+                                });
                             }
                         }
                     }
@@ -882,7 +1707,13 @@ impl Actor {
                     }
                     Action::Drop_(ch) => {
                         if let Some(item) = self.equip_drop(ch) {
-                            loc.at_mut(self.pos.coord).drop_item(item);
+                            let (id, coord) = (self.id, self.coord());
+                            self.emit(Event::ItemDropped {
+                                id: id,
+                                coord: coord,
+                                item: (*item).clone(),
+                            });
+                            loc.at_mut(coord).drop_item(item);
                         }
                     }
                     Action::Descend => {
@@ -890,9 +1721,25 @@ impl Actor {
                             self.descend();
                         }
                     }
+                    Action::Ascend => {
+                        if loc.at(self.coord()).tile().feature == Some(tile::Feature::StairsUp) {
+                            self.ascend();
+                        }
+                    }
                     Action::Ranged(target_coord) => {
-                        self.try_attack_ranged(loc, target_coord);
+                        self.try_attack_ranged(loc, target_coord, rng);
                     },
+                    Action::Cast(spell, target) => {
+                        self.cast(spell, target, loc, rng);
+                    }
+                    Action::Throw(ch, target_coord) => {
+                        self.throw(ch, loc, target_coord, rng);
+                    }
+                    Action::Shove => {
+                        if self.can_attack() {
+                            self.shove(loc, rng);
+                        }
+                    }
                     _ => {}
                 }
             } else if self.could_be_attack(action) &&
@@ -907,21 +1754,32 @@ impl Actor {
                         _ => old_pos.dir,
                     };
 
-                    let target_id = loc.actors_coord_to_id[&new_pos.coord];
-
-                    let mut target = loc.actors_byid.remove(&target_id).unwrap();
-                    self.attacks(dir, &mut target);
-                    loc.actors_byid.insert(target_id, target);
+                    // charging knocks the target back
+                    let charging = action == Action::Charge;
+                    if self.weapon_pattern() == Pattern::Sweep {
+                        for &angle in &[Left, Forward, Right] {
+                            let coord = self.head_rel(angle);
+                            self.melee(old_pos.dir + angle, coord, charging, loc, rng);
+                        }
+                    } else {
+                        self.melee(dir, new_pos.coord, charging, loc, rng);
+                    }
                     // Can't attack twice
                     break;
                 } else if loc.at(new_pos.coord).tile().feature == Some(tile::Door(false)) {
                     // walked into door: open it
                     loc.map[new_pos.coord].add_feature(tile::Door(true));
-                    self.open_door(loc);
+                    self.open_door(new_pos.coord, loc);
                     // Can't charge through the doors
                     break;
                 } else if old_pos.coord == new_pos.coord && old_pos.dir != new_pos.dir {
                     // we've rotated
+                    let id = self.id;
+                    self.emit(Event::Turned {
+                        id: id,
+                        from: old_pos.dir,
+                        to: new_pos.dir,
+                    });
                     self.moved(loc, new_pos);
                 } else if old_pos.coord != new_pos.coord && loc.at(new_pos.coord).is_passable() &&
                     !loc.actors_coord_to_id.contains_key(&new_pos.coord) {
@@ -930,6 +1788,11 @@ impl Actor {
                         debug_assert!(!loc.actors_coord_to_id.contains_key(&new_pos.coord));
                         let id = loc.actors_coord_to_id.remove(&self.pos.coord).unwrap();
                         // we've moved
+                        self.emit(Event::Moved {
+                            id: id,
+                            from: old_pos,
+                            to: new_pos,
+                        });
                         self.moved(loc, new_pos);
                         loc.actors_coord_to_id.insert(new_pos.coord, id);
                     } else {
@@ -938,7 +1801,7 @@ impl Actor {
                             if self.can_dig() &&
                                 self.can_dig_at_angle(angle) &&
                                     loc.at(new_pos.coord).tile().can_dig_through() {
-                                        self.dig(angle, loc)
+                                        self.dig(angle, loc, rng)
                                     }
                         }
 
@@ -957,25 +1820,268 @@ impl Actor {
             .map_or(false, |item| item.can_dig())
     }
 
-    pub fn dig(&mut self, angle : Angle, loc : &mut Location) {
+    pub fn dig(&mut self, angle : Angle, loc : &mut Location, rng: &mut game::Rng) {
         assert!(self.can_dig_at_angle(angle));
         let target_coord = self.head_rel(angle);
-        loc.at_mut(target_coord).tile().dig();
+        let digging = self.skills.level(Skill::Digging);
+        if loc.at_mut(target_coord).tile().dig(digging, rng) {
+            let id = self.id;
+            self.emit(Event::TileDug {
+                id: id,
+                coord: target_coord,
+            });
+        }
         self.substract_melee_sp_cost();
         self.noise_makes(9);
+        self.practise(Skill::Digging);
     }
 
 
-    pub fn could_be_attack(&self, action : Action) -> bool {
+    pub fn can_cast(&self, spell: Spell) -> bool {
+        self.spells.contains(&spell) && self.mp >= spell.mp_cost()
+    }
+
+    pub fn can_blink_to(&self, coord: Coordinate, loc: &Location) -> bool {
+        self.sees(coord) && self.coord().distance(coord) <= BLINK_RANGE &&
+        loc.at(coord).is_passable()
+    }
+
+    fn cast(&mut self, spell: Spell, target: Option<Coordinate>, loc: &mut Location, rng: &mut game::Rng) {
+        self.mp -= spell.mp_cost();
+        let id = self.id;
+        self.emit(Event::SpellCast {
+            id: id,
+            spell: spell,
+        });
+        self.noise_makes(5);
+
+        match spell {
+            Spell::Flame | Spell::Shockwave => {
+                for coord in spell.area(self.pos) {
+                    if self.in_los(coord) {
+                        self.spell_hits(spell, coord, loc, rng);
+                    }
+                }
+            }
+            Spell::Bolt => {
+                for coord in spell.area(self.pos) {
+                    if loc.actors_coord_to_id.contains_key(&coord) {
+                        self.spell_hits(spell, coord, loc, rng);
+                        break;
+                    }
+                    if !loc.at(coord).tile().is_passable() {
+                        break;
+                    }
+                }
+            }
+            Spell::Blink => {
+                if let Some(coord) = target {
+                    let from = self.pos;
+                    let to = Position {
+                        coord: coord,
+                        dir: from.dir,
+                    };
+                    loc.actors_coord_to_id.remove(&from.coord);
+                    self.emit(Event::Moved {
+                        id: id,
+                        from: from,
+                        to: to,
+                    });
+                    self.moved(loc, to);
+                    loc.actors_coord_to_id.insert(coord, id);
+                }
+            }
+        }
+    }
+
+    fn spell_hits(&mut self, spell: Spell, coord: Coordinate, loc: &mut Location, rng: &mut game::Rng) {
+        let target_id = match loc.actors_coord_to_id.get(&coord) {
+            Some(&id) => id,
+            None => return,
+        };
+        let mut target = match loc.actors_byid.remove(&target_id) {
+            Some(target) => target,
+            None => return,
+        };
+
+        let dmg = spell.base_dmg() + rng.gen_range(0..(self.stats.base.int + 1));
+        let id = self.id;
+        self.emit(Event::SpellHit {
+            attacker: id,
+            target: target_id,
+            spell: spell,
+            dmg: dmg,
+        });
+
+        target.hp -= dmg;
+        target.harmed_by(id);
+        target.noise_makes(7);
+        if spell == Spell::Shockwave {
+            target.add_status(Status::Stunned, SHOCKWAVE_STUN, 0);
+        }
+
+        loc.actors_byid.insert(target_id, target);
+    }
+
+    /// Does it make sense to perform `action` in the current state?
+    ///
+    /// Walking into walls and such is always fine, but eg. descending
+    /// requires standing on the stairs.
+    pub fn can_perform(&self, action : Action, loc : &Location) -> bool {
         match action {
-            Action::Charge => true,
-            Action::Move(angle) => {
-                match angle {
-                    Left | Right | Forward => true,
-                    _ => false,
+            Action::Wait |
+            Action::Turn(_) |
+            Action::Move(_) |
+            Action::Charge |
+            Action::Spin(_) => true,
+            Action::Equip(ch) => self.can_equip(ch) && !self.blocked_by_curse(ch, true),
+            Action::Drop_(ch) => self.item_letter_taken(ch) && !self.blocked_by_curse(ch, false),
+            Action::Ranged(_) => self.can_attack_ranged(),
+            Action::Throw(ch, target_coord) => {
+                self.items_backpack.contains_key(&ch) && target_coord != self.coord()
+            }
+            Action::Pick(i) => {
+                loc.at(self.head()).items().get(i).map_or(false, |item| self.has_room_for(item))
+            }
+            Action::Shove => loc.actors_coord_to_id.contains_key(&self.head()),
+            Action::Descend => loc.at(self.coord()).tile().feature == Some(tile::Feature::Stairs),
+            Action::Ascend => {
+                loc.at(self.coord()).tile().feature == Some(tile::Feature::StairsUp) &&
+                (loc.level > 0 || self.carries_orb())
+            }
+            Action::Cast(spell, target) => {
+                self.can_cast(spell) &&
+                match spell {
+                    Spell::Blink => target.map_or(false, |coord| self.can_blink_to(coord, loc)),
+                    _ => true,
                 }
             }
+        }
+    }
+
+    pub fn could_be_attack(&self, action : Action) -> bool {
+        match action {
+            Action::Charge => true,
+            Action::Move(angle) => self.can_attack_at_angle(angle),
             _ => false,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+    use game::artifact::Artifact;
+    use game::item::Type;
+    use game::score::Record;
+
+    fn actor() -> Actor {
+        Actor::new(Race::Human, Position::new(Coordinate::new(0, 0), Direction::YZ))
+    }
+
+    fn item(type_: Type) -> Box<Item> {
+        Box::new(Item::new(type_, vec![]))
+    }
+
+    #[test]
+    fn pick_merges_stacks() {
+        let mut a = actor();
+        assert!(a.pick_item(item(Type::Arrow)).is_none());
+        assert!(a.pick_item(item(Type::Knife)).is_none());
+        assert!(a.pick_item(item(Type::Arrow)).is_none());
+
+        assert_eq!(a.items_backpack.len(), 2);
+        assert_eq!(a.items_backpack[&'a'].type_(), Type::Arrow);
+        assert_eq!(a.items_backpack[&'a'].quantity(), 2);
+    }
+
+    #[test]
+    fn take_splits_stacks() {
+        let mut a = actor();
+        for _ in 0..3 {
+            a.pick_item(item(Type::Arrow));
+        }
+
+        // used for dropping and throwing
+        let taken = a.take_from_backpack('a').unwrap();
+        assert_eq!(taken.quantity(), 1);
+        assert_eq!(a.items_backpack[&'a'].quantity(), 2);
+
+        let dropped = a.equip_drop('a').unwrap();
+        assert_eq!(dropped.quantity(), 1);
+        assert_eq!(a.items_backpack[&'a'].quantity(), 1);
+
+        a.return_to_backpack('a', taken);
+        assert_eq!(a.items_backpack[&'a'].quantity(), 2);
+
+        a.take_from_backpack('a');
+        assert_eq!(a.take_from_backpack('a').unwrap().quantity(), 1);
+        assert!(!a.item_letter_taken('a'));
+        assert!(a.take_from_backpack('a').is_none());
+    }
+
+    #[test]
+    fn equip_keeps_stacks() {
+        let mut a = actor();
+        for _ in 0..3 {
+            a.pick_item(item(Type::Arrow));
+        }
+        assert!(!a.can_equip('a'));
+
+        // even if asked to, nothing is lost
+        a.equip_switch('a');
+        assert_eq!(a.items_backpack[&'a'].quantity(), 3);
+        assert!(a.items_equipped.is_empty());
+    }
+
+    #[test]
+    fn free_letters_run_out() {
+        let mut a = actor();
+        a.pick_item(item(Type::Arrow));
+        for _ in 0..51 {
+            assert!(a.pick_item(item(Type::Knife)).is_none());
+        }
+        assert_eq!(a.free_letter(), None);
+
+        assert!(!a.has_room_for(&item(Type::Knife)));
+        assert!(a.pick_item(item(Type::Knife)).is_some());
+
+        // stacks still fit
+        assert!(a.has_room_for(&item(Type::Arrow)));
+        assert!(a.pick_item(item(Type::Arrow)).is_none());
+        assert_eq!(a.items_backpack[&'a'].quantity(), 2);
+    }
+
+    #[test]
+    fn bleeding_kill_credits_the_attacker() {
+        let mut loc = Location::new(0, 0, &mut BTreeSet::new());
+        let mut player = actor();
+        player.set_player();
+        player.equip(Box::new(Item::new_artifact(Artifact::Gutripper)), 'a');
+        let player_id = loc.spawn_player(player);
+        let victim_id = loc.spawn(Actor::new(Race::Rat,
+                                             Position::new(Coordinate::new(3, 0), Direction::YZ)));
+
+        // a hit that doesn't kill right away, but makes it bleed
+        let mut victim = loc.actors_byid.remove(&victim_id).unwrap();
+        let mut player = loc.actors_byid.remove(&player_id).unwrap();
+        victim.hp = 1;
+        victim.harmed_by(player_id);
+        player.artifact_hits(&mut victim, 0);
+        assert!(victim.statuses.has(Status::Bleeding));
+        loc.actors_byid.insert(victim_id, victim);
+        loc.actors_byid.insert(player_id, player);
+        loc.take_events();
+
+        loc.skip_act(victim_id);
+
+        assert!(loc.actors_byid[&victim_id].is_dead());
+        assert!(loc.player().xp > 0);
+
+        let mut record = Record::default();
+        record.observe(&loc.take_events(), &loc);
+        assert_eq!(record.kills, 1);
+    }
+}
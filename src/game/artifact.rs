@@ -0,0 +1,128 @@
+//! Unique, hand-designed items
+//!
+//! Each of them can be found at most once per game (see `item::random`).
+
+use super::item::{Affix, Feature, Type};
+use super::status::Status;
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
+pub enum Artifact {
+    Gutripper,
+    Venomfang,
+    Skullsplitter,
+    Dawnspear,
+    WardensPlate,
+    HelmOfClarity,
+    Windstriders,
+}
+
+/// What makes an artifact more than its stats
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Special {
+    /// Inflicts a status on every target hit
+    OnHit {
+        status: Status,
+        duration: u32,
+        strength: i32,
+    },
+    /// Heals the wielder by half of the damage dealt
+    Drain,
+    /// The wearer is immune to a status
+    Wards(Status),
+}
+
+impl Artifact {
+    pub fn all() -> [Artifact; 7] {
+        [Artifact::Gutripper,
+         Artifact::Venomfang,
+         Artifact::Skullsplitter,
+         Artifact::Dawnspear,
+         Artifact::WardensPlate,
+         Artifact::HelmOfClarity,
+         Artifact::Windstriders]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Artifact::Gutripper => "Gutripper",
+            Artifact::Venomfang => "Venomfang",
+            Artifact::Skullsplitter => "Skullsplitter",
+            Artifact::Dawnspear => "Dawnspear",
+            Artifact::WardensPlate => "the Warden's Plate",
+            Artifact::HelmOfClarity => "the Helm of Clarity",
+            Artifact::Windstriders => "the Windstriders",
+        }
+    }
+
+    pub fn type_(&self) -> Type {
+        match *self {
+            Artifact::Gutripper => Type::Knife,
+            Artifact::Venomfang => Type::Sword,
+            Artifact::Skullsplitter => Type::Axe,
+            Artifact::Dawnspear => Type::Spear,
+            Artifact::WardensPlate => Type::Plate,
+            Artifact::HelmOfClarity => Type::Helmet,
+            Artifact::Windstriders => Type::Boots,
+        }
+    }
+
+    /// Fixed stat block, on top of the one of its type
+    pub fn features(&self) -> Vec<Feature> {
+        let f = |affix, magnitude| {
+            Feature {
+                affix: affix,
+                magnitude: magnitude,
+            }
+        };
+
+        match *self {
+            Artifact::Gutripper => vec![f(Affix::Damage, 2), f(Affix::Accuracy, 1)],
+            Artifact::Venomfang => vec![f(Affix::Accuracy, 2)],
+            Artifact::Skullsplitter => vec![f(Affix::Damage, 2), f(Affix::Stamina, -2)],
+            Artifact::Dawnspear => vec![f(Affix::Light, 2), f(Affix::Damage, 1)],
+            Artifact::WardensPlate => vec![f(Affix::Armor, 2), f(Affix::Regeneration, 3)],
+            Artifact::HelmOfClarity => vec![f(Affix::Vision, 2)],
+            Artifact::Windstriders => vec![f(Affix::Evasion, 3)],
+        }
+    }
+
+    pub fn special(&self) -> Special {
+        match *self {
+            Artifact::Gutripper => {
+                Special::OnHit {
+                    status: Status::Bleeding,
+                    duration: 4,
+                    strength: 1,
+                }
+            }
+            Artifact::Venomfang => {
+                Special::OnHit {
+                    status: Status::Poisoned,
+                    duration: 6,
+                    strength: 1,
+                }
+            }
+            Artifact::Skullsplitter => {
+                Special::OnHit {
+                    status: Status::Stunned,
+                    duration: 1,
+                    strength: 0,
+                }
+            }
+            Artifact::Dawnspear => Special::Drain,
+            Artifact::WardensPlate => Special::Wards(Status::Bleeding),
+            Artifact::HelmOfClarity => Special::Wards(Status::Confused),
+            Artifact::Windstriders => Special::Wards(Status::Slowed),
+        }
+    }
+
+    /// Shallowest dungeon level it can be found on
+    pub fn min_level(&self) -> i32 {
+        match *self {
+            Artifact::Gutripper | Artifact::Windstriders => 1,
+            Artifact::Venomfang | Artifact::HelmOfClarity => 3,
+            Artifact::Dawnspear | Artifact::WardensPlate => 5,
+            Artifact::Skullsplitter => 6,
+        }
+    }
+}
@@ -1,5 +1,8 @@
 use super::actor;
 
+/// The deepest level; holds the orb instead of the stairs down
+pub const FINAL_LEVEL: u32 = 9;
+
 pub const ELF_STATS: actor::Stats = actor::Stats {
     int: 3,
     dex: 4,
@@ -12,6 +15,7 @@ pub const ELF_STATS: actor::Stats = actor::Stats {
     infravision: 3,
     vision: 15,
     regeneration: 0,
+    speed: 11,
 };
 
 pub const HUMAN_STATS: actor::Stats = actor::Stats {
@@ -26,6 +30,7 @@ pub const HUMAN_STATS: actor::Stats = actor::Stats {
     infravision: 1,
     vision: 10,
     regeneration: 0,
+    speed: 10,
 };
 
 
@@ -41,6 +46,7 @@ pub const DWARF_STATS: actor::Stats = actor::Stats {
     infravision: 2,
     vision: 10,
     regeneration: 0,
+    speed: 9,
 };
 
 
@@ -56,6 +62,7 @@ pub const RAT_STATS: actor::Stats = actor::Stats {
     infravision: 1,
     vision: 6,
     regeneration: 0,
+    speed: 15,
 };
 
 pub const GOBLIN_STATS: actor::Stats = actor::Stats {
@@ -70,6 +77,7 @@ pub const GOBLIN_STATS: actor::Stats = actor::Stats {
     infravision: 2,
     vision: 10,
     regeneration: 0,
+    speed: 11,
 };
 
 pub const TROLL_STATS: actor::Stats = actor::Stats {
@@ -84,4 +92,62 @@ pub const TROLL_STATS: actor::Stats = actor::Stats {
     infravision: 2,
     vision: 10,
     regeneration: 10,
+    speed: 7,
 };
+
+/// Energy spent by most of the actions
+///
+/// Every turn actors regain energy equal to their speed, so an actor
+/// of speed `ACTION_COST` acts exactly once per turn.
+pub const ACTION_COST: i32 = 10;
+pub const CHARGE_COST: i32 = 15;
+pub const SPIN_COST: i32 = 15;
+/// Additional cost of (un)equipping an item
+pub const EQUIP_COST: i32 = 20;
+pub const EQUIP_BODY_COST: i32 = 40;
+/// Additional cost of using (eg. quaffing) an item
+pub const USE_COST: i32 = 20;
+
+/// Chance that a confused actor moves in a random direction
+pub const CONFUSION_CHANCE: f64 = 0.5;
+
+/// How far `Spell::Bolt` flies
+pub const BOLT_RANGE: i32 = 8;
+/// How far `Spell::Blink` can teleport
+pub const BLINK_RANGE: i32 = 5;
+/// Own ticks the targets of `Spell::Shockwave` stay stunned
+pub const SHOCKWAVE_STUN: u32 = 2;
+
+/// Every that many hexes of distance cost a shot 1 point of accuracy
+pub const RANGED_ACC_FALLOFF: i32 = 2;
+/// How far items can be thrown
+pub const THROW_RANGE: i32 = 5;
+
+/// Damage from being pushed into a wall or another actor
+pub const COLLISION_DMG: i32 = 3;
+/// Own ticks an actor pushed into water stays stunned
+pub const WATER_STUN: u32 = 2;
+
+/// XP needed for level 2; every next level needs that much more
+/// than the previous one (see `actor::xp_for_level`)
+pub const XP_BASE: u32 = 50;
+/// Max HP gained on level-up
+pub const LEVEL_HP: i32 = 3;
+pub const LEVEL_SP: i32 = 2;
+pub const LEVEL_MP: i32 = 2;
+
+/// Uses needed to raise a skill from level 0; every level needs that many more
+pub const SKILL_PRACTICE: u32 = 10;
+pub const SKILL_MAX: u32 = 10;
+
+/// Own ticks an item has to be worn by the player to get identified
+pub const IDENTIFY_WEAR: u32 = 100;
+
+/// Every that many dungeon levels raise the maximum magnitude of affixes
+pub const AFFIX_LEVELS: i32 = 3;
+/// Chance that an affix is harmful
+pub const NEGATIVE_AFFIX_CHANCE: f64 = 0.25;
+/// Chance that a piece of gear with a harmful affix is cursed
+pub const CURSE_CHANCE: f64 = 0.5;
+/// Chance that a random item is an artifact, while there are any left
+pub const ARTIFACT_CHANCE: f64 = 0.05;
@@ -1,16 +1,44 @@
-use super::{Location, Action};
-use super::actor::{self, Actor};
+use std::cmp;
+use std::collections::BTreeSet;
+
+use rand::SeedableRng;
+
+use super::{Location, Action, Rng, tile};
+use super::actor::{self, Actor, Attribute};
+use super::journal::{Journal, Input};
+use super::error::{Error, Result};
+use super::event::{Event, SubscriberId, Subscribers};
+use super::score::Record;
+use super::knowledge::Knowledge;
+use super::artifact::Artifact;
 use util;
 use ai::{self, Ai};
 
-#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+/// Phase of the current turn
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
 enum State {
     PlayerMove,
     AiMove,
     TurnDone,
 }
 
-
+/// The game: all the levels, the player and the turn sequencing
+///
+/// Every turn goes like this:
+///
+/// * `start_turn`,
+/// * `player_act` if `needs_player_input`, `player_skip_act` otherwise,
+/// * `one_actor_tick` until `is_turn_done`.
+///
+/// Actors with energy left (see `Actor::energy`) get to act again in the
+/// same turn, so `player_act` might be needed again before it's done.
+///
+/// Calls out of order fail with `error::Error::WrongPhase`.
+/// `advance_until_player_input` does all but `player_act` at once.
+///
+/// Everything that happens is reported as `event::Event`s to all the
+/// subscribers (see `subscribe`).
+#[derive(Serialize, Deserialize)]
 pub struct Engine {
     turn: u64,
     location_cur: usize,
@@ -18,20 +46,94 @@ pub struct Engine {
 
     ids_to_move: Vec<actor::Id>,
     state : State,
+
+    seed: u64,
+    rng: Rng,
+
+    journal: Journal,
+    record: Record,
+    knowledge: Knowledge,
+    /// Generated so far; each can appear only once per game
+    artifacts: BTreeSet<Artifact>,
+
+    #[serde(skip)]
+    subscribers: Subscribers,
 }
 
 impl Engine {
-    pub fn new() -> Self {
-        let location = Location::new(0);
+    /// New game; same `seed` and same input always give the same game
+    pub fn new(seed: u64) -> Self {
+        let mut artifacts = BTreeSet::new();
+        let location = Location::new(0, Engine::level_seed(seed, 0), &mut artifacts);
+        let mut rng = Rng::seed_from_u64(seed);
+        let knowledge = Knowledge::new(&mut rng);
         Engine {
             location_cur: 0,
             locations: vec![location],
             ids_to_move: vec![],
             turn: 0,
             state: State::TurnDone,
+            seed: seed,
+            rng: rng,
+            journal: Journal::new(seed),
+            record: Default::default(),
+            knowledge: knowledge,
+            artifacts: artifacts,
+            subscribers: Default::default(),
         }
     }
 
+    /// Start collecting events for a new subscriber
+    ///
+    /// Subscriptions are not saved along with the game.
+    pub fn subscribe(&mut self) -> SubscriberId {
+        self.subscribers.subscribe()
+    }
+
+    pub fn unsubscribe(&mut self, id: SubscriberId) {
+        self.subscribers.unsubscribe(id)
+    }
+
+    /// Events that happened since the last poll by a given subscriber
+    pub fn poll_events(&mut self, id: SubscriberId) -> Vec<Event> {
+        self.subscribers.poll(id)
+    }
+
+    /// Kills, depth and the cause of death, if any
+    pub fn record(&self) -> &Record {
+        &self.record
+    }
+
+    /// What the player has identified so far
+    pub fn knowledge(&self) -> &Knowledge {
+        &self.knowledge
+    }
+
+    fn publish_events(&mut self) {
+        let events = self.current_location_mut().take_events();
+        self.record.observe(&events, &self.locations[self.location_cur]);
+        self.knowledge.observe(&events, &self.locations[self.location_cur]);
+        self.subscribers.publish(&events);
+    }
+
+    /// Seed of the stream used to generate a given level
+    ///
+    /// Levels are generated from their own streams, so their layout does not
+    /// depend on what happened in the game before they were entered.
+    fn level_seed(seed: u64, level: u32) -> u64 {
+        seed ^ (level as u64 + 1).wrapping_mul(0x9e37_79b9_7f4a_7c15)
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// All the input the game was given so far
+    pub fn journal(&self) -> &Journal {
+        &self.journal
+    }
+
+    /// The level the player is on
     pub fn current_location(&self) -> &Location {
         &self.locations[self.location_cur]
     }
@@ -45,40 +147,107 @@ impl Engine {
         self.turn
     }
 
+    /// Put the player character of a given race into the first level
     pub fn initial_spawn(&mut self, race: actor::Race) {
-        let pos = util::random_pos(0, 0);
+        let pos = util::random_pos(&mut self.rng, 0, 0);
         let mut player = Actor::new(race, pos);
         player.set_player();
+        self.journal.race = Some(race);
 
         self.current_location_mut().spawn_player(player);
+        self.publish_events();
+    }
+
+    /// Has the player escaped the dungeon with the orb?
+    pub fn is_won(&self) -> bool {
+        self.record.won
     }
 
+    /// Is it time for `player_act`?
     pub fn needs_player_input(&self) -> bool {
-        self.state == State::PlayerMove && self.player().can_act() && !self.player().is_dead()
+        self.state == State::PlayerMove && self.current_location().has_player() && !self.is_won() &&
+        self.player().can_act() && !self.player().is_dead()
     }
 
     pub fn player(&self) -> &Actor {
         self.current_location().player()
     }
 
-    pub fn checks_after_act(&mut self, actor_id: actor::Id) {
-        if actor_id == self.current_location().player_id() &&
-           self.current_location().player().descended() {
-            let mut player = self.current_location_mut().remove(actor_id).unwrap();
-            self.location_cur += 1;
-            self.locations.push(Location::new(self.location_cur as u32));
-            player.pos = util::random_pos(0, 0);
-            let _player = self.current_location_mut().spawn_player(player);
+    fn checks_after_act(&mut self, actor_id: actor::Id) {
+        self.publish_events();
+
+        if actor_id == self.current_location().player_id() && self.player().ascended() &&
+           self.location_cur == 0 {
+            self.record.won = true;
+            self.subscribers.publish(&[Event::Won]);
+            self.ids_to_move.clear();
             self.end_turn();
+            return;
+        }
+
+        let level_change = if actor_id != self.current_location().player_id() {
+            None
+        } else if self.player().descended() {
+            Some(self.location_cur + 1)
+        } else if self.player().ascended() {
+            Some(self.location_cur - 1)
         } else {
+            None
+        };
+
+        if let Some(level) = level_change {
+            self.change_level(level);
+            self.ids_to_move.clear();
+            self.end_turn();
+        } else if !self.ids_to_move.is_empty() {
             self.state = State::AiMove;
+        } else {
+            // Everyone had a go; another round for the ones with energy left
+            self.reload_actors_ids_to_move();
 
-            if self.ids_to_move.is_empty() {
+            if self.player().can_act() {
+                self.state = State::PlayerMove;
+            } else if !self.ids_to_move.is_empty() {
+                self.state = State::AiMove;
+            } else {
                 self.end_turn();
             }
         }
     }
 
+    /// Move the player to a given level, generating it on the first visit
+    ///
+    /// The player arrives on the staircase leading back to where they
+    /// came from.
+    fn change_level(&mut self, level: usize) {
+        let from = self.location_cur;
+        let mut player = self.current_location_mut().remove_player();
+
+        if level == self.locations.len() {
+            let seed = Engine::level_seed(self.seed, level as u32);
+            self.locations.push(Location::new(level as u32, seed, &mut self.artifacts));
+        }
+        self.location_cur = level;
+        self.record.max_depth = cmp::max(self.record.max_depth, level as u32);
+
+        let arrival = if level > from {
+            tile::StairsUp
+        } else {
+            tile::Stairs
+        };
+        let (x, y) = self.current_location()
+                         .find_feature(arrival)
+                         .map_or((0, 0), |coord| (coord.x, coord.y));
+        player.pos = util::random_pos(&mut self.rng, x, y);
+        self.current_location_mut().spawn_player(player);
+
+        self.subscribers.publish(&[Event::LevelChanged {
+                                       from: from as u32,
+                                       to: level as u32,
+                                   }]);
+        self.publish_events();
+    }
+
     fn reload_actors_ids_to_move(&mut self) {
         let current_location = &self.locations[self.location_cur];
         let player_id = current_location.player_id();
@@ -86,56 +255,153 @@ impl Engine {
                                 .actors_alive_ids()
                                 .iter()
                                 .filter(|&&id| id != player_id)
+                                .filter(|&id| current_location.actors_byid[id].can_act())
                                 .cloned());
     }
 
-    // player first move
-    pub fn player_act(&mut self, action: Action) {
-        //assert!(self.state == State::PlayerMove); // redundant; in next assert
-        assert!(self.needs_player_input());
+    fn check_phase(&self, state: State) -> Result<()> {
+        if !self.current_location().has_player() {
+            Err(Error::NoPlayer)
+        } else if self.record.won {
+            Err(Error::GameWon)
+        } else if self.state != state {
+            Err(Error::WrongPhase)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Perform the player's action; the player moves first in every turn
+    pub fn player_act(&mut self, action: Action) -> Result<()> {
+        self.check_phase(State::PlayerMove)?;
+
+        if self.player().is_dead() {
+            return Err(Error::PlayerDead);
+        }
+
+        if !self.needs_player_input() {
+            return Err(Error::WrongPhase);
+        }
+
+        if !self.player().can_perform(action, self.current_location()) {
+            return Err(Error::InvalidAction(action));
+        }
 
         let player_id = self.current_location().player_id();
 
-        self.current_location_mut().act(player_id, action);
+        self.journal.record(self.turn, Input::Act(action));
+        self.locations[self.location_cur].act(player_id, action, &mut self.rng);
 
         self.checks_after_act(player_id);
+
+        Ok(())
     }
 
-    pub fn player_skip_act(&mut self) {
-        assert!(self.state == State::PlayerMove);
-        assert!(!self.needs_player_input());
+    /// Spend one of the player's attribute points
+    ///
+    /// Doesn't take any time.
+    pub fn player_raise_attribute(&mut self, attr: Attribute) -> Result<()> {
+        self.check_phase(State::PlayerMove)?;
+
+        if !self.needs_player_input() {
+            return Err(Error::WrongPhase);
+        }
+
+        if !self.player().can_raise_attribute() {
+            return Err(Error::NoAttributePoints);
+        }
+
         let player_id = self.current_location().player_id();
-        self.current_location_mut().skip_act(player_id);
+
+        self.journal.record(self.turn, Input::Raise(attr));
+        self.current_location_mut()
+            .actors_byid
+            .get_mut(&player_id)
+            .unwrap()
+            .raise_attribute(attr);
+
+        Ok(())
+    }
+
+    /// Let the turn pass for a player that can't act (eg. is dead)
+    pub fn player_skip_act(&mut self) -> Result<()> {
+        self.check_phase(State::PlayerMove)?;
+
+        if self.needs_player_input() {
+            return Err(Error::WrongPhase);
+        }
+
+        let player_id = self.current_location().player_id();
+        self.journal.record(self.turn, Input::Skip);
+        self.locations[self.location_cur].skip_act(player_id);
 
         self.checks_after_act(player_id);
+
+        Ok(())
     }
 
-    // then everybody else one by one
-    pub fn one_actor_tick(&mut self) -> actor::Id {
-        assert!(self.state == State::AiMove);
-        assert!(!self.needs_player_input());
+    /// Let the next monster act, after the player did
+    ///
+    /// Returns the id of the actor that acted.
+    pub fn one_actor_tick(&mut self) -> Result<actor::Id> {
+        self.check_phase(State::AiMove)?;
 
-        let actor_id = self.ids_to_move.pop().unwrap();
+        let actor_id = match self.ids_to_move.pop() {
+            Some(id) => id,
+            None => return Err(Error::WrongPhase),
+        };
 
         let player_id = self.current_location().player_id();
-        assert!(actor_id != player_id);
+        debug_assert!(actor_id != player_id);
 
         let actor = &self.current_location().actors_byid[&actor_id].clone();
+        let location = &mut self.locations[self.location_cur];
         if actor.can_act() {
             let mut ai = ai::Simple;
-            let action = ai.action(actor_id, self);
-            self.current_location_mut().act(actor_id, action);
+            let action = ai.action(actor_id, location, &mut self.rng);
+            location.act(actor_id, action, &mut self.rng);
         } else {
-            self.current_location_mut().skip_act(actor_id);
+            location.skip_act(actor_id);
         }
 
         self.checks_after_act(actor_id);
 
-        actor_id
+        Ok(actor_id)
+    }
+
+    /// Run the game until the player has to decide what to do
+    ///
+    /// Starts a new turn if needed, passes the ticks of a player that can't
+    /// act and runs all the monsters' ticks. Returns ids of actors that acted,
+    /// in order.
+    ///
+    /// Fails with `Error::PlayerDead` if the player is dead and there was
+    /// nothing left to run.
+    pub fn advance_until_player_input(&mut self) -> Result<Vec<actor::Id>> {
+        let mut acted = vec![];
+
+        loop {
+            match self.state {
+                State::TurnDone => self.start_turn()?,
+                State::AiMove => acted.push(self.one_actor_tick()?),
+                State::PlayerMove => {
+                    if self.needs_player_input() {
+                        return Ok(acted);
+                    } else if self.player().is_dead() {
+                        return if acted.is_empty() {
+                            Err(Error::PlayerDead)
+                        } else {
+                            Ok(acted)
+                        };
+                    }
+                    self.player_skip_act()?;
+                }
+            }
+        }
     }
 
     fn end_turn(&mut self) {
-        self.current_location_mut().post_turn();
+        self.locations[self.location_cur].post_turn(&mut self.rng);
         self.state = State::TurnDone;
     }
 
@@ -143,11 +409,12 @@ impl Engine {
         self.state == State::TurnDone
     }
 
-    pub fn start_turn(&mut self) {
-        assert!(self.state == State::TurnDone);
+    pub fn start_turn(&mut self) -> Result<()> {
+        self.check_phase(State::TurnDone)?;
         self.turn += 1;
+        self.current_location_mut().pre_turn();
         self.reload_actors_ids_to_move();
         self.state = State::PlayerMove;
+        Ok(())
     }
-
 }
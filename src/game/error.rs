@@ -0,0 +1,38 @@
+use std::fmt;
+use std;
+
+use super::Action;
+
+/// Reasons for `Engine` to refuse a request
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Error {
+    /// There's no player character (see `Engine::initial_spawn`)
+    NoPlayer,
+    /// Not possible in the current phase of the turn
+    WrongPhase,
+    /// The player character is dead
+    PlayerDead,
+    /// The player has left the dungeon with the orb
+    GameWon,
+    /// The action can't be performed by the player right now
+    InvalidAction(Action),
+    /// The player character has no attribute points to spend
+    NoAttributePoints,
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::NoPlayer => write!(f, "no player character"),
+            Error::WrongPhase => write!(f, "wrong phase of the turn"),
+            Error::PlayerDead => write!(f, "player character is dead"),
+            Error::GameWon => write!(f, "game is won"),
+            Error::InvalidAction(action) => write!(f, "invalid action: {:?}", action),
+            Error::NoAttributePoints => write!(f, "no attribute points to spend"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
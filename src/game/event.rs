@@ -0,0 +1,213 @@
+use std::collections::{HashMap, VecDeque};
+
+use hex2d::{Coordinate, Direction, Position};
+
+use super::actor::{self, Arc, Slot};
+use super::item::Item;
+use super::skill::Skill;
+use super::spell::Spell;
+use super::status::Status;
+
+/// Details of a single attack, including the rolls
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Attack {
+    pub attacker: actor::Id,
+    pub target: actor::Id,
+    pub ranged: bool,
+    /// Side of the target that was attacked; accuracy and damage from
+    /// the rear are multiplied (see `Item::backstab_mult`)
+    pub arc: Arc,
+    /// The target defended with a shield
+    pub shielded: bool,
+    /// Accuracy and damage were halved for the lack of SP
+    pub exhausted: bool,
+    pub acc: i32,
+    pub ev: i32,
+    pub success: bool,
+    /// Damage before the armor roll
+    pub dmg: i32,
+    /// Damage stopped by the armor
+    pub absorbed: i32,
+}
+
+impl Attack {
+    /// Damage that the target has taken
+    pub fn dmg_dealt(&self) -> i32 {
+        if self.success {
+            self.dmg - self.absorbed
+        } else {
+            0
+        }
+    }
+}
+
+/// What stopped an actor being pushed
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub enum Obstacle {
+    Wall,
+    Water,
+    Actor(actor::Id),
+}
+
+/// Something that happened in the game
+///
+/// Actor ids refer to the level the player is on; `LevelChanged`
+/// marks the switch to a different one.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub enum Event {
+    Moved {
+        id: actor::Id,
+        from: Position,
+        to: Position,
+    },
+    Turned {
+        id: actor::Id,
+        from: Direction,
+        to: Direction,
+    },
+    Attacked(Attack),
+    Shoved {
+        id: actor::Id,
+        target: actor::Id,
+        success: bool,
+    },
+    /// Knocked back or shoved by `by`; `to` is `None` if it didn't budge
+    Pushed {
+        id: actor::Id,
+        by: actor::Id,
+        from: Coordinate,
+        to: Option<Coordinate>,
+    },
+    /// Hurt by being pushed into something, or something pushed into it
+    Collided {
+        id: actor::Id,
+        obstacle: Obstacle,
+        dmg: i32,
+    },
+    /// Followed by the `Attacked` event of the free attack
+    OpportunityAttack {
+        attacker: actor::Id,
+        target: actor::Id,
+    },
+    Died {
+        id: actor::Id,
+        race: actor::Race,
+        coord: Coordinate,
+    },
+    DoorOpened {
+        id: actor::Id,
+        coord: Coordinate,
+    },
+    ItemPicked {
+        id: actor::Id,
+        coord: Coordinate,
+        item: Item,
+    },
+    ItemDropped {
+        id: actor::Id,
+        coord: Coordinate,
+        item: Item,
+    },
+    ItemEquipped {
+        id: actor::Id,
+        slot: Slot,
+        item: Item,
+    },
+    ItemUnequipped {
+        id: actor::Id,
+        slot: Slot,
+        item: Item,
+    },
+    ItemUsed {
+        id: actor::Id,
+        item: Item,
+    },
+    /// Its features became known to the player
+    ItemIdentified {
+        id: actor::Id,
+        item: Item,
+    },
+    /// Landed at `coord`, unless it has shattered
+    ItemThrown {
+        id: actor::Id,
+        coord: Coordinate,
+        item: Item,
+    },
+    /// Broke through a wall; failed attempts aren't reported
+    TileDug {
+        id: actor::Id,
+        coord: Coordinate,
+    },
+    NoiseMade {
+        id: actor::Id,
+        coord: Coordinate,
+        loudness: i32,
+    },
+    SpellCast {
+        id: actor::Id,
+        spell: Spell,
+    },
+    /// Spells always hit and ignore armor
+    SpellHit {
+        attacker: actor::Id,
+        target: actor::Id,
+        spell: Spell,
+        dmg: i32,
+    },
+    StatusGained {
+        id: actor::Id,
+        status: Status,
+    },
+    StatusEnded {
+        id: actor::Id,
+        status: Status,
+    },
+    LevelUp {
+        id: actor::Id,
+        level: u32,
+    },
+    SkillImproved {
+        id: actor::Id,
+        skill: Skill,
+        level: u32,
+    },
+    LevelChanged {
+        from: u32,
+        to: u32,
+    },
+    /// The player left the dungeon with the orb
+    Won,
+}
+
+pub type SubscriberId = u32;
+
+/// Queues of events not yet consumed by each of the subscribers
+#[derive(Clone, Debug, Default)]
+pub struct Subscribers {
+    next_id: SubscriberId,
+    queues: HashMap<SubscriberId, VecDeque<Event>>,
+}
+
+impl Subscribers {
+    pub fn subscribe(&mut self) -> SubscriberId {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.queues.insert(id, VecDeque::new());
+        id
+    }
+
+    pub fn unsubscribe(&mut self, id: SubscriberId) {
+        self.queues.remove(&id);
+    }
+
+    pub fn publish(&mut self, events: &[Event]) {
+        for queue in self.queues.values_mut() {
+            queue.extend(events.iter().cloned());
+        }
+    }
+
+    /// Take all the events queued for a subscriber, oldest first
+    pub fn poll(&mut self, id: SubscriberId) -> Vec<Event> {
+        self.queues.get_mut(&id).map_or(vec![], |queue| queue.drain(..).collect())
+    }
+}
@@ -1,14 +1,20 @@
 use super::actor::{self, Actor, Slot};
+use super::skill::Skill;
+use super::Rng as GameRng;
+use super::artifact::{Artifact, Special};
+use super::consts::{AFFIX_LEVELS, ARTIFACT_CHANCE, CURSE_CHANCE, IDENTIFY_WEAR,
+                    NEGATIVE_AFFIX_CHANCE};
+use super::knowledge::Knowledge;
 use rand::prelude::SliceRandom;
 use rand::{self, Rng};
-use rand::distributions::{Distribution, Standard};
+use rand::distributions::Distribution;
 
 use std::cmp;
+use std::collections::BTreeSet;
 use std::fmt::{self, Write};
 
 use self::Category::*;
 use self::Type::*;
-use self::Feature::*;
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub enum Category {
@@ -17,9 +23,22 @@ pub enum Category {
     Armor,
     Misc,
     Consumable,
+    Quest,
+    Ammo,
 }
 
+/// Hexes hit by a melee attack, relative to the attacker's facing
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Pattern {
+    /// Just the hex attacked
+    Single,
+    /// Two hexes forward, attacking only straight ahead
+    Reach,
+    /// All three hexes in front
+    Sweep,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
 pub enum Type {
     Knife,
     Bow,
@@ -27,6 +46,8 @@ pub enum Type {
     Pickaxe,
     Axe,
     HealthPotion,
+    /// Lifts the curses off the equipped items
+    PurityPotion,
     Junk,
     Leather,
     Plate,
@@ -34,6 +55,11 @@ pub enum Type {
     Boots,
     Buckler,
     Cloak,
+    Arrow,
+    ThrowingKnife,
+    Spear,
+    /// The goal of the game
+    Orb,
 }
 
 impl Type {
@@ -45,6 +71,7 @@ impl Type {
             Sword => "sword",
             Axe => "axe",
             HealthPotion => "health potion",
+            PurityPotion => "potion of purity",
             Plate => "plate armor",
             Leather => "leather armor",
             Helmet => "helmet",
@@ -52,8 +79,17 @@ impl Type {
             Buckler => "buckler",
             Cloak => "cloak",
             Pickaxe => "pickaxe",
+            Arrow => "arrow",
+            ThrowingKnife => "throwing knife",
+            Spear => "spear",
+            Orb => "orb of hexes",
         }
     }
+
+    /// Types that look different in every game until identified
+    pub fn with_appearance() -> [Type; 2] {
+        [HealthPotion, PurityPotion]
+    }
 }
 
 impl fmt::Display for Type {
@@ -62,30 +98,115 @@ impl fmt::Display for Type {
     }
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Debug)]
-pub enum Feature {
+/// Kind of a magical property
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub enum Affix {
     Infravision,
     Light,
     Regeneration,
+    /// Melee and ranged
+    Accuracy,
+    /// Melee and ranged
+    Damage,
+    Armor,
+    Evasion,
+    Vision,
+    /// Max SP
+    Stamina,
 }
 
-impl Feature {
+impl Affix {
+    pub fn all() -> [Affix; 9] {
+        [Affix::Infravision,
+         Affix::Light,
+         Affix::Regeneration,
+         Affix::Accuracy,
+         Affix::Damage,
+         Affix::Armor,
+         Affix::Evasion,
+         Affix::Vision,
+         Affix::Stamina]
+    }
+
     pub fn description(&self) -> &str {
         match *self {
-            Infravision => "infravision",
-            Light => "light",
-            Regeneration => "regeneration",
+            Affix::Infravision => "infravision",
+            Affix::Light => "light",
+            Affix::Regeneration => "regeneration",
+            Affix::Accuracy => "accuracy",
+            Affix::Damage => "damage",
+            Affix::Armor => "armor",
+            Affix::Evasion => "evasion",
+            Affix::Vision => "vision",
+            Affix::Stamina => "stamina",
+        }
+    }
+
+    /// Light and regeneration can't go below zero
+    pub fn can_be_negative(&self) -> bool {
+        match *self {
+            Affix::Light | Affix::Regeneration => false,
+            _ => true,
         }
     }
 
+    /// Stat points per point of magnitude
+    fn scale(&self) -> i32 {
+        match *self {
+            Affix::Vision | Affix::Stamina => 2,
+            _ => 1,
+        }
+    }
+}
+
+/// An affix with its rolled magnitude; negative ones are harmful
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Feature {
+    pub affix: Affix,
+    pub magnitude: i32,
+}
+
+impl Feature {
+    /// Roll an affix for an item found on a given dungeon level
+    pub fn random(rng: &mut GameRng, level: i32) -> Feature {
+        let affix = *Affix::all().choose(rng).unwrap();
+        let magnitude = rng.gen_range(1..(2 + cmp::max(0, level) / AFFIX_LEVELS));
+        let negative = affix.can_be_negative() && rng.gen_bool(NEGATIVE_AFFIX_CHANCE);
+
+        Feature {
+            affix: affix,
+            magnitude: if negative {
+                -magnitude
+            } else {
+                magnitude
+            },
+        }
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.magnitude < 0
+    }
 
     pub fn stats(&self) -> actor::EffectiveStats {
         let mut s: actor::EffectiveStats = Default::default();
-
-        match *self {
-            Infravision => s.base.infravision += 1,
-            Light => s.light_emision += 1,
-            Regeneration => s.base.regeneration += 1,
+        let m = self.magnitude * self.affix.scale();
+
+        match self.affix {
+            Affix::Infravision => s.base.infravision += m,
+            Affix::Light => s.light_emision += cmp::max(0, m) as u32,
+            Affix::Regeneration => s.base.regeneration += cmp::max(0, m) as u32,
+            Affix::Accuracy => {
+                s.melee_acc += m;
+                s.ranged_acc += m;
+            }
+            Affix::Damage => {
+                s.melee_dmg += m;
+                s.ranged_dmg += m;
+            }
+            Affix::Armor => s.base.ac += m,
+            Affix::Evasion => s.base.ev += m,
+            Affix::Vision => s.base.vision += m,
+            Affix::Stamina => s.base.max_sp += m,
         }
 
         s
@@ -94,32 +215,151 @@ impl Feature {
 
 impl fmt::Display for Feature {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.description())
+        write!(f, "{} {:+}", self.affix.description(), self.magnitude)
     }
 }
 
-impl Distribution<Feature> for Standard {
-    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Feature {
-        match rng.gen_range(0..3) {
-            0 => Infravision,
-            1 => Light,
-            2 => Regeneration,
-            _ => panic!(),
-        }
-    }
-}
-
-#[derive(Clone, Eq, PartialEq, Debug)]
+/// An item with its (magical) features
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
 pub struct Item {
     type_: Type,
     features: Vec<Feature>,
+    /// Can't be taken off until uncursed
+    cursed: bool,
+    artifact: Option<Artifact>,
+    /// Items in the stack; see `is_stackable`
+    quantity: u32,
+    /// The player knows the features
+    identified: bool,
+    /// Own ticks the player has had it equipped
+    worn: u32,
 }
 
 impl Item {
     pub fn new(t: Type, features: Vec<Feature>) -> Item {
-        Item {
+        let mut item = Item {
             type_: t,
             features: features,
+            cursed: false,
+            artifact: None,
+            quantity: 1,
+            identified: false,
+            worn: 0,
+        };
+        item.identified = !item.hides_features();
+        item
+    }
+
+    /// Artifacts are famous enough to be recognized right away
+    pub fn new_artifact(artifact: Artifact) -> Item {
+        let mut item = Item::new(artifact.type_(), artifact.features());
+        item.artifact = Some(artifact);
+        item.identified = true;
+        item
+    }
+
+    pub fn artifact(&self) -> Option<Artifact> {
+        self.artifact
+    }
+
+    pub fn special(&self) -> Option<Special> {
+        self.artifact.map(|a| a.special())
+    }
+
+    pub fn type_(&self) -> Type {
+        self.type_
+    }
+
+    pub fn quantity(&self) -> u32 {
+        self.quantity
+    }
+
+    /// Can more of it share a backpack letter or a tile?
+    pub fn is_stackable(&self) -> bool {
+        match self.type_ {
+            HealthPotion | PurityPotion | Arrow | ThrowingKnife | Junk => true,
+            _ => false,
+        }
+    }
+
+    /// Can `other` be merged into this stack?
+    pub fn stacks_with(&self, other: &Item) -> bool {
+        self.is_stackable() &&
+        Item {
+            quantity: self.quantity,
+            ..other.clone()
+        } == *self
+    }
+
+    /// Put `other` on the stack; see `stacks_with`
+    pub fn merge(&mut self, other: Box<Item>) {
+        debug_assert!(self.stacks_with(&other));
+        self.quantity += other.quantity;
+    }
+
+    /// Take one item off a stack of more
+    pub fn split_one(&mut self) -> Box<Item> {
+        debug_assert!(self.quantity > 1);
+        self.quantity -= 1;
+        Box::new(Item {
+            quantity: 1,
+            ..self.clone()
+        })
+    }
+
+    fn fmt_quantity(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.quantity > 1 {
+            write!(f, " (x{})", self.quantity)?;
+        }
+        Ok(())
+    }
+
+    /// Are the features of this kind of item unknown until identified?
+    fn hides_features(&self) -> bool {
+        match self.category() {
+            Weapon | RangedWeapon | Armor => true,
+            _ => false,
+        }
+    }
+
+    pub fn is_cursed(&self) -> bool {
+        self.cursed
+    }
+
+    pub fn uncurse(&mut self) {
+        self.cursed = false;
+    }
+
+    pub fn is_identified(&self) -> bool {
+        self.identified
+    }
+
+    pub fn identify(&mut self) {
+        self.identified = true;
+    }
+
+    /// Count an own tick of the player having it equipped
+    ///
+    /// Returns true if that has just identified it.
+    pub fn wear(&mut self) -> bool {
+        if self.identified {
+            return false;
+        }
+
+        self.worn += 1;
+        if self.worn >= IDENTIFY_WEAR {
+            self.identify();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Describe it as seen by someone with a given `Knowledge`
+    pub fn known<'a>(&'a self, knowledge: &'a Knowledge) -> Known<'a> {
+        Known {
+            item: self,
+            knowledge: knowledge,
         }
     }
 
@@ -133,14 +373,24 @@ impl Item {
 
     pub fn category(&self) -> Category {
         match self.type_ {
-            Knife | Sword | Axe | Pickaxe => Weapon,
+            Knife | Sword | Axe | Pickaxe | Spear => Weapon,
             Bow => RangedWeapon,
             Leather | Plate | Helmet | Boots | Buckler | Cloak => Armor,
-            HealthPotion => Consumable,
-            Junk => Misc,
+            HealthPotion | PurityPotion => Consumable,
+            Junk | ThrowingKnife => Misc,
+            Orb => Quest,
+            Arrow => Ammo,
         }
     }
 
+    pub fn is_ammo(&self) -> bool {
+        self.category() == Ammo
+    }
+
+    pub fn is_orb(&self) -> bool {
+        self.type_ == Orb
+    }
+
     pub fn can_dig(&self) -> bool {
         self.type_ == Pickaxe
     }
@@ -151,7 +401,7 @@ impl Item {
 
     pub fn slot(&self) -> Option<Slot> {
         match self.type_ {
-            Axe | Sword | Knife => Some(Slot::RHand),
+            Axe | Sword | Knife | Spear => Some(Slot::RHand),
             Pickaxe => Some(Slot::RHand),
             Bow => Some(Slot::RHand),
             Leather | Plate => Some(Slot::Body),
@@ -170,6 +420,7 @@ impl Item {
             Plate => {
                 s.base.ac = 4;
                 s.base.ev = -2;
+                s.base.speed = -2;
             }
             Leather => {
                 s.base.ac = 1;
@@ -180,10 +431,6 @@ impl Item {
                 s.base.infravision = -1;
             }
             Boots => s.base.ev = 1,
-            Buckler => {
-                s.base.ev = 1;
-                s.base.ac = 1
-            }
             Cloak => {
                 s.base.ev = 1;
             }
@@ -199,9 +446,18 @@ impl Item {
                 s.melee_dmg += 4;
                 s.melee_str_req = 5;
             }
+            Spear => {
+                s.melee_dmg += 3;
+                s.melee_str_req = 4;
+            }
+            Bow => {
+                s.ranged_dmg = 3;
+                s.ranged_range = 8;
+            }
             Pickaxe => {
                 s.melee_dmg += 1;
                 s.melee_str_req = 6;
+                s.base.speed = -1;
             }
             _ => {}
         }
@@ -212,6 +468,76 @@ impl Item {
         s
     }
 
+    pub fn pattern(&self) -> Pattern {
+        match self.type_ {
+            Spear => Pattern::Reach,
+            Axe => Pattern::Sweep,
+            _ => Pattern::Single,
+        }
+    }
+
+    /// Accuracy and damage multiplier of attacks from behind
+    pub fn backstab_mult(&self) -> i32 {
+        match self.type_ {
+            Knife => 3,
+            _ => 2,
+        }
+    }
+
+    /// Knocks the target back on a hit
+    pub fn is_heavy(&self) -> bool {
+        match self.type_ {
+            Axe | Pickaxe => true,
+            _ => false,
+        }
+    }
+
+    /// Skill trained and used when fighting with it
+    pub fn skill(&self) -> Option<Skill> {
+        match self.type_ {
+            Knife | Sword => Some(Skill::Blades),
+            Axe | Pickaxe => Some(Skill::Axes),
+            Spear => Some(Skill::Polearms),
+            Bow => Some(Skill::Bows),
+            _ => None,
+        }
+    }
+
+    /// Armor and evasion added against attacks from the shield side
+    pub fn shield(&self) -> Option<(i32, i32)> {
+        match self.type_ {
+            Buckler => Some((1, 2)),
+            _ => None,
+        }
+    }
+
+    /// Counterattacks a missed attack from the front
+    pub fn ripostes(&self) -> bool {
+        self.type_ == Sword
+    }
+
+    /// Breaks when thrown, applying its effect to whoever it hits
+    pub fn shatters(&self) -> bool {
+        self.category() == Consumable
+    }
+
+    pub fn thrown_acc(&self) -> i32 {
+        match self.type_ {
+            Knife | ThrowingKnife => 2,
+            Axe | Arrow => 0,
+            _ => -2,
+        }
+    }
+
+    pub fn thrown_dmg(&self) -> i32 {
+        match self.type_ {
+            Knife | ThrowingKnife => 4,
+            Axe => 3,
+            Sword | Pickaxe | Arrow => 2,
+            _ => 1,
+        }
+    }
+
     pub fn is_usable(&self) -> bool {
         self.category() == Consumable
     }
@@ -226,51 +552,179 @@ impl Item {
                 astate.hp = cmp::min(astate.hp, astate.stats.base.max_hp);
                 true
             }
+            PurityPotion => {
+                astate.uncurse_equipped();
+                true
+            }
             _ => false,
         }
     }
 }
 
+/// Features and curses are left out until the item is identified
 impl fmt::Display for Item {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.type_)?;
+        if !self.identified {
+            write!(f, "{}", self.type_)?;
+            return self.fmt_quantity(f);
+        }
 
-        for feature in &self.features {
-            write!(f, " of {}", feature)?;
+        if self.cursed {
+            write!(f, "cursed ")?;
+        }
+        match self.artifact {
+            Some(artifact) => write!(f, "{} ({})", artifact.name(), self.type_)?,
+            None => write!(f, "{}", self.type_)?,
         }
 
-        Ok(())
+        for (i, feature) in self.features.iter().enumerate() {
+            write!(f, "{}{}", if i == 0 { " [" } else { ", " }, feature)?;
+        }
+        if !self.features.is_empty() {
+            write!(f, "]")?;
+        }
+
+        self.fmt_quantity(f)
     }
 }
 
+/// `Item` as described to someone with a given `Knowledge`
+pub struct Known<'a> {
+    item: &'a Item,
+    knowledge: &'a Knowledge,
+}
+
+impl<'a> fmt::Display for Known<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.knowledge.unknown_appearance(self.item.type_) {
+            Some(appearance) => {
+                write!(f, "{}", appearance)?;
+                self.item.fmt_quantity(f)
+            }
+            None => write!(f, "{}", self.item),
+        }
+    }
+}
 
-pub fn random(level: i32) -> Box<Item> {
+
+/// Random item appropriate for a given dungeon level
+///
+/// Sometimes it's an artifact that is not among the `spawned` ones yet.
+pub fn random(rng: &mut GameRng, level: i32, spawned: &mut BTreeSet<Artifact>) -> Box<Item> {
+    if rng.gen_bool(ARTIFACT_CHANCE) {
+        let candidates: Vec<_> = Artifact::all()
+                                     .iter()
+                                     .cloned()
+                                     .filter(|a| a.min_level() <= level && !spawned.contains(a))
+                                     .collect();
+        if let Some(&artifact) = candidates.choose(rng) {
+            spawned.insert(artifact);
+            return Box::new(Item::new_artifact(artifact));
+        }
+    }
 
     let a = -(level / 2);
     let b = level + 2;
-    let mut rng = rand::thread_rng();
     let lvrange = rand::distributions::Uniform::new(a, b);
-    let r = lvrange.sample(&mut rng) + lvrange.sample(&mut rng) +
-            lvrange.sample(&mut rng);
-
-    let mut features = vec![];
-    let mut chance = level;
-    const PER_LOOP: i32 = 30;
-    let looprange = rand::distributions::Uniform::new(0, PER_LOOP);
-    while looprange.sample(&mut rng) < chance {
-        features.push(rand::random());
-        chance = cmp::max(0, chance - PER_LOOP);
-    }
-
-    Box::new(Item::new(match r {
-                           1 => *[Knife, Pickaxe].choose(&mut rng).unwrap(),
-                           2 => HealthPotion,
-                           3 => *[Bow, Cloak].choose(&mut rng).unwrap(),
-                           5 => *[Helmet, Sword].choose(&mut rng).unwrap(),
-                           6 => Leather,
-                           8 => *[Boots, Buckler].choose(&mut rng).unwrap(),
-                           10 => *[Plate, Axe].choose(&mut rng).unwrap(),
-                           _ => Junk,
-                       },
-                       features))
+    let r = lvrange.sample(rng) + lvrange.sample(rng) +
+            lvrange.sample(rng);
+
+    let type_ = match r {
+        0 => PurityPotion,
+        1 => *[Knife, Pickaxe].choose(rng).unwrap(),
+        2 => HealthPotion,
+        3 => *[Bow, Cloak].choose(rng).unwrap(),
+        4 => Arrow,
+        5 => *[Helmet, Sword].choose(rng).unwrap(),
+        6 => Leather,
+        7 => *[Arrow, ThrowingKnife].choose(rng).unwrap(),
+        8 => *[Boots, Buckler].choose(rng).unwrap(),
+        9 => Spear,
+        10 => *[Plate, Axe].choose(rng).unwrap(),
+        _ => Junk,
+    };
+    let mut item = Item::new(type_, vec![]);
+    item.quantity = match type_ {
+        Arrow => rng.gen_range(3..9),
+        ThrowingKnife => rng.gen_range(2..5),
+        _ => 1,
+    };
+
+    // only gear is enchanted; features would do nothing on the rest
+    if item.slot().is_some() {
+        let mut chance = level;
+        const PER_LOOP: i32 = 30;
+        let looprange = rand::distributions::Uniform::new(0, PER_LOOP);
+        while looprange.sample(rng) < chance {
+            item.features.push(Feature::random(rng, level));
+            chance = cmp::max(0, chance - PER_LOOP);
+        }
+
+        if item.features.iter().any(|f| f.is_negative()) {
+            item.cursed = rng.gen_bool(CURSE_CHANCE);
+        }
+    }
+
+    Box::new(item)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+
+    #[test]
+    fn only_gear_has_features() {
+        let mut rng = GameRng::seed_from_u64(0);
+        let mut spawned = BTreeSet::new();
+
+        for _ in 0..1000 {
+            let item = random(&mut rng, 10, &mut spawned);
+            if item.slot().is_none() {
+                assert!(item.features.is_empty(), "{:?}", item);
+                assert!(!item.is_cursed(), "{:?}", item);
+            }
+        }
+    }
+
+    #[test]
+    fn stacks() {
+        let mut rng = GameRng::seed_from_u64(0);
+        let mut spawned = BTreeSet::new();
+
+        let mut potions = Item::new(HealthPotion, vec![]);
+        for _ in 0..1000 {
+            let item = random(&mut rng, 10, &mut spawned);
+            if item.type_() == HealthPotion {
+                assert!(potions.stacks_with(&item));
+                potions.merge(item);
+            }
+        }
+        assert!(potions.quantity() > 1);
+
+        assert!(!potions.stacks_with(&Item::new(PurityPotion, vec![])));
+        assert!(!Item::new(Knife, vec![]).stacks_with(&Item::new(Knife, vec![])));
+    }
+
+    #[test]
+    fn split_one() {
+        let mut arrows = Item::new(Arrow, vec![]);
+        arrows.merge(Box::new(Item::new(Arrow, vec![])));
+        arrows.merge(Box::new(Item::new(Arrow, vec![])));
+
+        let arrow = arrows.split_one();
+        assert_eq!(arrow.quantity(), 1);
+        assert_eq!(arrows.quantity(), 2);
+        assert!(arrows.stacks_with(&arrow));
+    }
+    #[test]
+    fn every_weapon_trains_a_skill() {
+        for &type_ in &[Knife, Bow, Sword, Pickaxe, Axe, Spear] {
+            let item = Item::new(type_, vec![]);
+            assert!(item.category() == Weapon || item.is_ranged_weapon(), "{:?}", type_);
+            assert!(item.skill().is_some(), "{:?}", type_);
+        }
+        assert_eq!(Item::new(Spear, vec![]).skill(), Some(Skill::Polearms));
+    }
 }
@@ -0,0 +1,75 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use bincode;
+
+use super::Action;
+use super::actor::{Attribute, Race};
+use super::save;
+
+/// Bump in every release that changes the rules, as old journals would
+/// replay differently
+const VERSION: u32 = 1;
+
+/// Player input fed to the `Engine`
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub enum Input {
+    /// `Engine::player_act`
+    Act(Action),
+    /// `Engine::player_skip_act`
+    Skip,
+    /// `Engine::player_raise_attribute`
+    Raise(Attribute),
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Entry {
+    pub turn: u64,
+    pub input: Input,
+}
+
+/// Everything needed to replay a game
+///
+/// As the `Engine` is deterministic, the seed, the race picked by the player
+/// and every input fed to the engine are enough to reproduce the whole game.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Journal {
+    pub seed: u64,
+    pub race: Option<Race>,
+    pub entries: Vec<Entry>,
+}
+
+impl Journal {
+    pub fn new(seed: u64) -> Self {
+        Journal {
+            seed: seed,
+            race: None,
+            entries: vec![],
+        }
+    }
+
+    pub fn record(&mut self, turn: u64, input: Input) {
+        self.entries.push(Entry {
+            turn: turn,
+            input: input,
+        });
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> save::Result<()> {
+        save::write_atomically(path, |writer| {
+            bincode::serialize_into(&mut *writer, &VERSION)?;
+            bincode::serialize_into(writer, self)?;
+            Ok(())
+        })
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> save::Result<Journal> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let version: u32 = bincode::deserialize_from(&mut reader)?;
+        if version != VERSION {
+            return Err(save::Error::Version(version));
+        }
+        Ok(bincode::deserialize_from(&mut reader)?)
+    }
+}
@@ -0,0 +1,69 @@
+//! What the player has learned about the items in the current game
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use rand::prelude::SliceRandom;
+
+use super::{Event, Location, Rng};
+use super::item::Type;
+
+/// Looks of the potions, assigned to potion types anew in every game
+const POTION_APPEARANCES: [&'static str; 6] = ["murky potion",
+                                               "bubbling potion",
+                                               "glowing potion",
+                                               "smoky potion",
+                                               "viscous potion",
+                                               "fizzy potion"];
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Knowledge {
+    /// Index into `POTION_APPEARANCES` of every type that has one
+    appearances: BTreeMap<Type, usize>,
+    /// Types identified by the player
+    known: BTreeSet<Type>,
+}
+
+impl Knowledge {
+    /// Shuffle the appearances for a new game
+    pub fn new(rng: &mut Rng) -> Self {
+        let mut looks: Vec<usize> = (0..POTION_APPEARANCES.len()).collect();
+        looks.shuffle(rng);
+
+        Knowledge {
+            appearances: Type::with_appearance().iter().cloned().zip(looks).collect(),
+            known: BTreeSet::new(),
+        }
+    }
+
+    pub fn is_known(&self, type_: Type) -> bool {
+        !self.appearances.contains_key(&type_) || self.known.contains(&type_)
+    }
+
+    /// How an unidentified type looks; `None` once it is known
+    pub fn unknown_appearance(&self, type_: Type) -> Option<&'static str> {
+        if self.is_known(type_) {
+            None
+        } else {
+            self.appearances.get(&type_).map(|&i| POTION_APPEARANCES[i])
+        }
+    }
+
+    pub fn learn(&mut self, type_: Type) {
+        self.known.insert(type_);
+    }
+
+    /// Learn from what the player did in a given level
+    pub fn observe(&mut self, events: &[Event], loc: &Location) {
+        if !loc.has_player() {
+            return;
+        }
+        let player_id = loc.player_id();
+
+        for event in events {
+            match *event {
+                Event::ItemUsed { id, ref item } if id == player_id => self.learn(item.type_()),
+                _ => {}
+            }
+        }
+    }
+}
@@ -1,5 +1,6 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::hash::BuildHasherDefault;
+use std::mem;
 
 use generate;
 
@@ -12,33 +13,46 @@ use super::tile;
 use super::item::Item;
 use super::Action;
 use super::actor::{self, Actor};
+use super::artifact::Artifact;
+use super::event::Event;
 use super::{LightMap, Map, Items};
-use super::Noise;
+use super::{Noise, Rng, Visibility};
 
-#[derive(Clone, Debug)]
+/// One level of the dungeon with everything in it
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Location {
     pub actors_byid: HashMap<actor::Id, Actor>, // id -> State
     pub actors_coord_to_id: HashMap<Coordinate, u32>, // coord -> id
     pub actors_dead: HashSet<actor::Id>,
     pub actors_counter: u32,
+    #[serde(with = "::game::save::simple_map")]
     pub map: Map,
     pub items: Items, // items on the floor
+    #[serde(with = "::game::save::simple_map")]
     pub light_map: LightMap, // light intensity at a given coordinate
     pub level: i32,
     player_id: Option<actor::Id>,
+    /// What the player knew about this level when leaving it
+    player_known: Visibility,
+    player_known_areas: Visibility,
+    /// Events not yet taken by the `Engine`
+    #[serde(skip)]
+    events: Vec<Event>,
 }
 
 impl Location {
-    pub fn new(level: u32) -> Location {
+    /// Generate a level; see `generate::gen_level`
+    pub fn new(level: u32, seed: u64, artifacts: &mut BTreeSet<Artifact>) -> Location {
 
-        let (map, gen_actors, items) = generate::gen_level(level);
+        let (map, gen_actors, items) = generate::gen_level(level, seed, artifacts);
 
         let mut actors: HashMap<u32, Actor> = Default::default();
         let mut actors_pos: HashMap<Coordinate, u32> = Default::default();
 
         let mut actors_counter = 0u32;
 
-        for (coord, astate) in gen_actors {
+        for (coord, mut astate) in gen_actors {
+            astate.id = actors_counter;
             actors_pos.insert(coord, actors_counter);
             actors.insert(actors_counter, astate);
             actors_counter += 1;
@@ -51,16 +65,28 @@ impl Location {
             actors_dead: Default::default(),
             items: items,
             map: map,
-            level: 0,
+            level: level as i32,
             light_map: Default::default(),
             player_id: None,
+            player_known: Default::default(),
+            player_known_areas: Default::default(),
+            events: vec![],
         }
     }
 
+    /// Take the events that happened since the last call, in order
+    pub fn take_events(&mut self) -> Vec<Event> {
+        mem::replace(&mut self.events, vec![])
+    }
+
     pub fn player_id(&self) -> actor::Id {
         self.player_id.unwrap()
     }
 
+    pub fn has_player(&self) -> bool {
+        self.player_id.is_some()
+    }
+
     pub fn player(&self) -> &Actor {
         &self.actors_byid[&self.player_id.unwrap()]
     }
@@ -83,12 +109,24 @@ impl Location {
         }
     }
 
+    /// Ids of all the actors, sorted
+    ///
+    /// `actors_byid` iteration order is random, so anything that affects the
+    /// game state has to go through these to keep games reproducible.
     pub fn actors_ids(&self) -> Vec<u32> {
-        self.actors_byid.keys().cloned().collect()
+        let mut ids: Vec<_> = self.actors_byid.keys().cloned().collect();
+        ids.sort();
+        ids
     }
 
     pub fn actors_alive_ids(&self) -> Vec<u32> {
-        self.actors_byid.keys().filter(|&id| !self.actors_byid[id].is_dead()).cloned().collect()
+        let mut ids: Vec<_> = self.actors_byid
+                                  .keys()
+                                  .filter(|&id| !self.actors_byid[id].is_dead())
+                                  .cloned()
+                                  .collect();
+        ids.sort();
+        ids
     }
 
     pub fn recalculate_light_map(&mut self) {
@@ -140,15 +178,27 @@ impl Location {
         self.light_map = light_map;
     }
 
+    /// Coordinate of the first tile with a given feature
+    pub fn find_feature(&self, feature: tile::Feature) -> Option<Coordinate> {
+        self.map.iter().find(|&(_, tile)| tile.feature == Some(feature)).map(|(coord, _)| *coord)
+    }
+
+    /// Put an actor into the level, at the nearest free place to its position
     pub fn spawn(&mut self, mut astate: Actor) -> actor::Id {
-        if self.actors_coord_to_id.contains_key(&astate.pos.coord) {
-            // TODO: Find an alternative place
-            unimplemented!();
+        if self.at(astate.pos.coord).is_occupied() {
+            let coord = {
+                let mut bfs = algo::bfs::Traverser::new(|coord| self.at(coord).tile().is_passable(),
+                                                        |coord| self.at(coord).is_passable(),
+                                                        astate.pos.coord);
+                bfs.find()
+            };
+            astate.pos.coord = coord.expect("no place to spawn an actor");
         }
         let id = self.actors_counter;
         self.actors_counter += 1;
 
         debug_assert!(!self.actors_coord_to_id.contains_key(&astate.pos.coord));
+        astate.id = id;
         self.actors_coord_to_id.insert(astate.pos.coord, id);
         astate.pre_spawn(self);
         self.actors_byid.insert(id, astate);
@@ -178,12 +228,23 @@ impl Location {
         Some(actor)
     }
 
-    pub fn spawn_player(&mut self, actor: Actor) -> actor::Id {
+    pub fn spawn_player(&mut self, mut actor: Actor) -> actor::Id {
         assert!(actor.is_player());
+        actor.known = mem::replace(&mut self.player_known, Default::default());
+        actor.known_areas = mem::replace(&mut self.player_known_areas, Default::default());
         self.player_id = Some(self.spawn(actor));
         self.player_id.unwrap()
     }
 
+    /// Take the player out of the level, remembering what they knew about it
+    pub fn remove_player(&mut self) -> Actor {
+        let id = self.player_id.take().unwrap();
+        let mut actor = self.remove(id).unwrap();
+        self.player_known = mem::replace(&mut actor.known, Default::default());
+        self.player_known_areas = mem::replace(&mut actor.known_areas, Default::default());
+        actor
+    }
+
     pub fn skip_act(&mut self, id: u32) {
         self.pre_any_tick();
         let mut actor = self.actors_byid.remove(&id).unwrap();
@@ -193,7 +254,7 @@ impl Location {
         self.post_any_tick();
     }
 
-    pub fn act(&mut self, id: u32, action: Action) {
+    pub fn act(&mut self, id: u32, action: Action, rng: &mut Rng) {
         self.pre_any_tick();
         let mut actor = self.actors_byid.remove(&id).unwrap();
 
@@ -202,15 +263,40 @@ impl Location {
             return;
         }
 
+        let from = actor.coord();
         actor.pre_own_tick();
-        actor.act(self, action);
+        actor.act(self, action, rng);
+
+        let walked = match action {
+            Action::Move(_) | Action::Charge | Action::Spin(_) => true,
+            _ => false,
+        };
+        if walked && actor.coord() != from {
+            self.attacks_of_opportunity(&mut actor, from, rng);
+        }
 
         actor.post_own_tick(self);
+        self.events.extend(actor.take_events());
         self.actors_byid.insert(id, actor);
         self.actors_byid.get_mut(&id).unwrap().post_action(action);
         self.post_any_tick();
     }
 
+    /// Free attacks of the enemies whose front arc `actor` has left at `from`
+    fn attacks_of_opportunity(&mut self, actor: &mut Actor, from: Coordinate, rng: &mut Rng) {
+        for id in self.actors_alive_ids() {
+            if actor.is_dead() {
+                break;
+            }
+
+            let enemy = self.actors_byid.get_mut(&id).unwrap();
+            if enemy.is_hostile_to(actor) && enemy.threatens(from) &&
+               !enemy.threatens(actor.coord()) {
+                enemy.attack_of_opportunity(from, actor, rng);
+            }
+        }
+    }
+
     pub fn pre_any_tick(&mut self) {
         for id in self.actors_alive_ids() {
             let mut actor = self.actors_byid.remove(&id).unwrap();
@@ -226,23 +312,39 @@ impl Location {
             self.actors_byid.insert(id, actor);
         }
 
+        // eg. noise made by the ones that got hit
+        for id in &self.actors_ids() {
+            let events = self.actors_byid.get_mut(id).unwrap().take_events();
+            self.events.extend(events);
+        }
+
         for id in &self.actors_ids() {
             if self.actors_byid[id].is_dead() && !self.actors_dead.contains(&id) {
                 let mut a = self.actors_byid.remove(&id).unwrap();
 
-                for (_, item) in &a.items_backpack {
-                    self.at_mut(a.pos.coord).drop_item(item.clone());
+                self.events.push(Event::Died {
+                    id: *id,
+                    race: a.race,
+                    coord: a.pos.coord,
+                });
+
+                let mut backpack: Vec<_> = a.items_backpack.drain().collect();
+                backpack.sort_by_key(|&(ch, _)| ch);
+                for (_, item) in backpack {
+                    self.at_mut(a.pos.coord).drop_item(item);
                 }
-                a.items_backpack.clear();
 
-                for (_, &(_, ref item)) in &a.items_equipped {
-                    self.at_mut(a.pos.coord).drop_item(item.clone());
+                let mut equipped: Vec<_> = a.items_equipped.drain().collect();
+                equipped.sort_by_key(|&(slot, _)| slot);
+                for (_, (_, item)) in equipped {
+                    self.at_mut(a.pos.coord).drop_item(item);
                 }
-                a.items_equipped.clear();
 
+                let xp = a.xp_value();
                 self.actors_byid.insert(*id, a);
 
                 self.actors_dead.insert(*id);
+                self.award_kill(*id, xp);
             }
         }
 
@@ -260,8 +362,33 @@ impl Location {
         self.recalculate_noise();
     }
 
-    pub fn post_turn(&mut self) {}
+    /// Give the XP for killing `dead_id` to whoever hurt it last
+    fn award_kill(&mut self, dead_id: actor::Id, xp: u32) {
+        let killer_id = self.actors_byid[&dead_id].last_harmer();
+
+        if let Some(killer) = killer_id.and_then(|id| self.actors_byid.get_mut(&id)) {
+            if !killer.is_dead() {
+                killer.gain_xp(xp);
+                let events = killer.take_events();
+                self.events.extend(events);
+            }
+        }
+    }
+
+    /// Give every actor the energy for the coming turn
+    pub fn pre_turn(&mut self) {
+        for id in self.actors_alive_ids() {
+            self.actors_byid.get_mut(&id).unwrap().gain_energy();
+        }
+    }
+
+    pub fn post_turn(&mut self, rng: &mut Rng) {
+        for id in self.actors_alive_ids() {
+            self.actors_byid.get_mut(&id).unwrap().regenerate(rng);
+        }
+    }
 
+    /// Query the state of a given coordinate
     pub fn at(&self, coord: Coordinate) -> At {
         At {
             coord: coord,
@@ -296,8 +423,9 @@ impl<'a> At<'a> {
             .map_or(def, |a| cond(&a))
     }
 
+    /// Map the item on top of the pile
     pub fn item_map_or<R, F: Fn(&Box<Item>) -> R>(&self, def: R, cond: F) -> R {
-        self.state.items.get(&self.coord).map_or(def, |i| cond(i))
+        self.items().last().map_or(def, |i| cond(i))
     }
 
     pub fn is_occupied(&self) -> bool {
@@ -334,8 +462,14 @@ impl<'a> At<'a> {
         }
     }
 
+    /// The item on top of the pile
     pub fn item(&self) -> Option<&'a Item> {
-        self.state.items.get(&self.coord).map(|i| &**i)
+        self.items().last().map(|i| &**i)
+    }
+
+    /// The whole pile, bottom first
+    pub fn items(&self) -> &'a [Box<Item>] {
+        self.state.items.get(&self.coord).map_or(&[][..], |pile| &pile[..])
     }
 }
 
@@ -350,10 +484,7 @@ impl<'a> AtMut<'a> {
             let mut bfs = algo::bfs::Traverser::new(|coord| {
                                                         self.state.at(coord).tile().is_passable()
                                                     },
-                                                    |coord| {
-                                                        self.state.at(coord).tile().is_passable() &&
-                                                        self.state.items.get(&coord).is_none()
-                                                    },
+                                                    |coord| self.state.at(coord).tile().is_passable(),
                                                     self.coord);
 
             bfs.find()
@@ -364,17 +495,26 @@ impl<'a> AtMut<'a> {
                 // destroy the item :/
             }
             Some(coord) => {
-                self.state.items.insert(coord, item);
+                let pile = self.state.items.entry(coord).or_insert_with(Vec::new);
+                match pile.iter().position(|i| i.stacks_with(&item)) {
+                    Some(i) => pile[i].merge(item),
+                    None => pile.push(item),
+                }
             }
         }
     }
 
-    pub fn pick_item(&mut self) -> Option<Box<Item>> {
-        if self.state.items.get(&self.coord).is_some() {
-            self.state.items.remove(&self.coord)
-        } else {
-            None
+    /// Take the item with a given index out of the pile
+    pub fn pick_item(&mut self, i: usize) -> Option<Box<Item>> {
+        let (item, empty) = match self.state.items.get_mut(&self.coord) {
+            Some(pile) if i < pile.len() => (pile.remove(i), pile.is_empty()),
+            _ => return None,
+        };
+
+        if empty {
+            self.state.items.remove(&self.coord);
         }
+        Some(item)
     }
 
     pub fn tile(&'a mut self) -> &'a mut tile::Tile {
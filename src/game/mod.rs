@@ -1,7 +1,10 @@
+//! Game state and rules
+
 use std::collections::{HashMap, HashSet};
 use std::hash::BuildHasherDefault;
 use simplemap::SimpleMap;
 use fnv::FnvHasher;
+use rand_pcg::Pcg64Mcg;
 
 use hex2d::Coordinate;
 
@@ -14,15 +17,27 @@ pub mod action;
 pub use self::action::Action;
 pub mod consts;
 pub mod item;
+pub mod artifact;
+pub mod knowledge;
 pub mod engine;
 pub use self::engine::*;
+pub mod error;
+pub mod event;
+pub use self::event::Event;
 pub mod tile;
 pub use self::tile::Tile;
 pub mod location;
 pub use self::location::Location;
+pub mod save;
+pub mod journal;
+pub use self::journal::Journal;
+pub mod score;
+pub mod status;
+pub mod skill;
+pub mod spell;
 
 
-#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
 pub enum Noise {
     Creature(actor::Race),
 }
@@ -40,5 +55,10 @@ pub type Visibility = HashSet<Coordinate, BuildHasherDefault<FnvHasher>>;
 pub type NoiseMap = HashMap<Coordinate, Noise, BuildHasherDefault<FnvHasher>>;
 pub type Map = SimpleMap<Coordinate, Tile, BuildHasherDefault<FnvHasher>>;
 pub type Actors = HashMap<Coordinate, Actor, BuildHasherDefault<FnvHasher>>;
-pub type Items = HashMap<Coordinate, Box<Item>, BuildHasherDefault<FnvHasher>>;
+/// Piles of items on the floor, the last one on top; never empty
+pub type Items = HashMap<Coordinate, Vec<Box<Item>>, BuildHasherDefault<FnvHasher>>;
 pub type LightMap = SimpleMap<Coordinate, u32, BuildHasherDefault<FnvHasher>>;
+
+/// Randomness affecting the game state must come from the `Engine`'s `Rng`
+/// (or a stream derived from its seed) to keep games reproducible.
+pub type Rng = Pcg64Mcg;
@@ -0,0 +1,141 @@
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::Path;
+use std;
+
+use bincode;
+
+use super::Engine;
+
+/// Bump in every release that changes the layout of serialized game state
+const VERSION: u32 = 1;
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    Format(bincode::Error),
+    Version(u32),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Io(ref err) => write!(f, "{}", err),
+            Error::Format(ref err) => write!(f, "{}", err),
+            Error::Version(version) => write!(f, "incompatible format version {}", version),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Error {
+        Error::Io(err)
+    }
+}
+
+impl From<bincode::Error> for Error {
+    fn from(err: bincode::Error) -> Error {
+        Error::Format(err)
+    }
+}
+
+pub fn exists<P: AsRef<Path>>(path: P) -> bool {
+    path.as_ref().is_file()
+}
+
+/// Write the whole game state to `path`
+pub fn save<P: AsRef<Path>>(engine: &Engine, path: P) -> Result<()> {
+    write_atomically(path, |writer| {
+        bincode::serialize_into(&mut *writer, &VERSION)?;
+        bincode::serialize_into(writer, engine)?;
+        Ok(())
+    })
+}
+
+/// Restore game state previously written with `save`
+pub fn load<P: AsRef<Path>>(path: P) -> Result<Engine> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let version: u32 = bincode::deserialize_from(&mut reader)?;
+    if version != VERSION {
+        return Err(Error::Version(version));
+    }
+    Ok(bincode::deserialize_from(&mut reader)?)
+}
+
+pub fn remove<P: AsRef<Path>>(path: P) -> Result<()> {
+    fs::remove_file(path)?;
+    Ok(())
+}
+
+pub fn rename<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> Result<()> {
+    fs::rename(from, to)?;
+    Ok(())
+}
+
+/// Write a file through a temporary one, renamed over `path` when complete
+///
+/// The previous contents of `path` are kept if anything fails.
+pub fn write_atomically<P, F>(path: P, write: F) -> Result<()>
+    where P: AsRef<Path>,
+          F: FnOnce(&mut BufWriter<File>) -> Result<()>
+{
+    let path = path.as_ref();
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+
+    let result = write_file(&tmp_path, write).and_then(|()| rename(&tmp_path, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+    result
+}
+
+fn write_file<P, F>(path: P, write: F) -> Result<()>
+    where P: AsRef<Path>,
+          F: FnOnce(&mut BufWriter<File>) -> Result<()>
+{
+    let mut writer = BufWriter::new(File::create(path)?);
+    write(&mut writer)?;
+    writer.flush()?;
+    writer.get_ref().sync_all()?;
+    Ok(())
+}
+
+/// `serde(with = ...)` support for `SimpleMap` based maps
+///
+/// Only explicitly set entries are stored; everything else falls back to the
+/// default value again after loading.
+pub mod simple_map {
+    use std::hash::{BuildHasher, Hash};
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use simplemap::SimpleMap;
+
+    pub fn serialize<K, V, H, S>(map: &SimpleMap<K, V, H>, serializer: S) -> Result<S::Ok, S::Error>
+        where K: Serialize + Eq + Hash,
+              V: Serialize + Default,
+              H: BuildHasher + Default,
+              S: Serializer
+    {
+        serializer.collect_seq(map.iter())
+    }
+
+    pub fn deserialize<'de, K, V, H, D>(deserializer: D) -> Result<SimpleMap<K, V, H>, D::Error>
+        where K: Deserialize<'de> + Eq + Hash,
+              V: Deserialize<'de> + Default,
+              H: BuildHasher + Default,
+              D: Deserializer<'de>
+    {
+        let entries: Vec<(K, V)> = Deserialize::deserialize(deserializer)?;
+
+        let mut map = SimpleMap::default();
+        for (k, v) in entries {
+            map[k] = v;
+        }
+
+        Ok(map)
+    }
+}
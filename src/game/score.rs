@@ -0,0 +1,184 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use bincode;
+
+use super::{Engine, Event, Location};
+use super::actor::{self, Race};
+use super::artifact::Artifact;
+use super::save;
+
+/// How many runs `HighScores` keeps
+pub const HIGH_SCORES_MAX: usize = 20;
+
+/// Added to the score of a won game
+pub const VICTORY_BONUS: u64 = 1000;
+
+/// Bump whenever the layout of `Entry` changes
+const VERSION: u32 = 1;
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub enum Death {
+    /// Killed in a fight with a monster of a given race
+    Killed(Race),
+    Unknown,
+}
+
+impl Death {
+    pub fn description(&self) -> String {
+        match *self {
+            Death::Killed(race) => format!("killed by a {}", race.description()),
+            Death::Unknown => "died".to_owned(),
+        }
+    }
+}
+
+/// How the game went so far
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Record {
+    pub kills: u32,
+    /// Deepest level reached
+    pub max_depth: u32,
+    pub death: Option<Death>,
+    /// Escaped the dungeon with the orb
+    pub won: bool,
+    /// Picked up by the player, in the order they were found
+    pub artifacts: Vec<Artifact>,
+}
+
+impl Record {
+    pub fn score(&self) -> u64 {
+        let bonus = if self.won {
+            VICTORY_BONUS
+        } else {
+            0
+        };
+
+        self.max_depth as u64 * 100 + self.kills as u64 * 10 + bonus
+    }
+
+    /// How the game has ended, or "quit" if it hasn't
+    pub fn end_description(&self) -> String {
+        end_description(self.won, self.death)
+    }
+
+    /// Account for the events that happened in a given level
+    pub fn observe(&mut self, events: &[Event], loc: &Location) {
+        if !loc.has_player() {
+            return;
+        }
+        let player_id = loc.player_id();
+        let killer = |id: actor::Id| loc.actors_byid.get(&id).and_then(|a| a.last_harmer());
+
+        for event in events {
+            match *event {
+                Event::ItemPicked { id, ref item, .. } if id == player_id => {
+                    if let Some(artifact) = item.artifact() {
+                        if !self.artifacts.contains(&artifact) {
+                            self.artifacts.push(artifact);
+                        }
+                    }
+                }
+                Event::Died { id, .. } if id == player_id => {
+                    self.death = Some(match killer(id).and_then(|k| loc.actors_byid.get(&k)) {
+                        Some(attacker) => Death::Killed(attacker.race),
+                        None => Death::Unknown,
+                    });
+                }
+                Event::Died { id, .. } => {
+                    if killer(id) == Some(player_id) {
+                        self.kills += 1;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// A finished run
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Entry {
+    pub score: u64,
+    pub race: Race,
+    pub depth: u32,
+    pub turn: u64,
+    pub kills: u32,
+    pub death: Option<Death>,
+    pub won: bool,
+    pub artifacts: Vec<Artifact>,
+    pub seed: u64,
+}
+
+impl Entry {
+    pub fn new(engine: &Engine) -> Self {
+        let record = engine.record();
+        Entry {
+            score: record.score(),
+            race: engine.player().race,
+            depth: record.max_depth,
+            turn: engine.turn(),
+            kills: record.kills,
+            death: record.death,
+            won: record.won,
+            artifacts: record.artifacts.clone(),
+            seed: engine.seed(),
+        }
+    }
+
+    pub fn end_description(&self) -> String {
+        end_description(self.won, self.death)
+    }
+}
+
+fn end_description(won: bool, death: Option<Death>) -> String {
+    if won {
+        "escaped with the orb".to_owned()
+    } else {
+        death.map_or("quit".to_owned(), |d| d.description())
+    }
+}
+
+/// Best runs, best first
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct HighScores {
+    pub entries: Vec<Entry>,
+}
+
+impl HighScores {
+    /// Put a run into the table
+    ///
+    /// Returns its rank, if it was good enough to be kept.
+    pub fn add(&mut self, entry: Entry) -> Option<usize> {
+        let rank = self.entries.iter().take_while(|e| e.score >= entry.score).count();
+        if rank >= HIGH_SCORES_MAX {
+            return None;
+        }
+
+        self.entries.insert(rank, entry);
+        self.entries.truncate(HIGH_SCORES_MAX);
+        Some(rank)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> save::Result<()> {
+        save::write_atomically(path, |writer| {
+            bincode::serialize_into(&mut *writer, &VERSION)?;
+            bincode::serialize_into(writer, self)?;
+            Ok(())
+        })
+    }
+
+    /// Load the table; empty if there's none yet, or it's of another version
+    pub fn load<P: AsRef<Path>>(path: P) -> save::Result<HighScores> {
+        if !save::exists(&path) {
+            return Ok(Default::default());
+        }
+        let mut reader = BufReader::new(File::open(path)?);
+        let version: u32 = bincode::deserialize_from(&mut reader)?;
+        if version != VERSION {
+            return Ok(Default::default());
+        }
+        Ok(bincode::deserialize_from(&mut reader)?)
+    }
+}
@@ -0,0 +1,173 @@
+//! Proficiencies that improve with practice
+
+use std::collections::BTreeMap;
+
+use super::actor::{EffectiveStats, Race};
+use super::consts::*;
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
+pub enum Skill {
+    /// Knives and swords
+    Blades,
+    /// Axes and pickaxes
+    Axes,
+    Bows,
+    /// Wearing and being hit in armor
+    Armor,
+    Dodging,
+    /// Moving quietly
+    Stealth,
+    Digging,
+    /// Spears
+    Polearms,
+}
+
+impl Skill {
+    pub fn all() -> [Skill; 8] {
+        [Skill::Blades,
+         Skill::Axes,
+         Skill::Polearms,
+         Skill::Bows,
+         Skill::Armor,
+         Skill::Dodging,
+         Skill::Stealth,
+         Skill::Digging]
+    }
+
+    pub fn description(&self) -> &'static str {
+        match *self {
+            Skill::Blades => "blades",
+            Skill::Axes => "axes",
+            Skill::Polearms => "polearms",
+            Skill::Bows => "bows",
+            Skill::Armor => "armor",
+            Skill::Dodging => "dodging",
+            Skill::Stealth => "stealth",
+            Skill::Digging => "digging",
+        }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Progress {
+    level: u32,
+    /// Uses since the last level
+    practice: u32,
+}
+
+/// Skill levels of an actor, in a stable order
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Skills {
+    progress: BTreeMap<Skill, Progress>,
+}
+
+impl Skills {
+    pub fn new(levels: &[(Skill, u32)]) -> Skills {
+        Skills {
+            progress: levels.iter()
+                            .map(|&(skill, level)| {
+                                (skill,
+                                 Progress {
+                                    level: level,
+                                    practice: 0,
+                                })
+                            })
+                            .collect(),
+        }
+    }
+
+    pub fn level(&self, skill: Skill) -> u32 {
+        self.progress.get(&skill).map_or(0, |p| p.level)
+    }
+
+    /// Count one use of `skill`
+    ///
+    /// Returns the new level, if it has improved.
+    pub fn practise(&mut self, skill: Skill) -> Option<u32> {
+        let progress = self.progress.entry(skill).or_insert_with(Default::default);
+        if progress.level >= SKILL_MAX {
+            return None;
+        }
+
+        progress.practice += 1;
+        if progress.practice < SKILL_PRACTICE * (progress.level + 1) {
+            return None;
+        }
+
+        progress.practice = 0;
+        progress.level += 1;
+        Some(progress.level)
+    }
+
+    /// Modify already calculated stats, given the skill of the wielded weapon
+    pub fn apply(&self, stats: &mut EffectiveStats, weapon: Option<Skill>) {
+        if let Some(skill) = weapon.filter(|&skill| skill != Skill::Bows) {
+            let level = self.level(skill) as i32;
+            stats.melee_acc += level;
+            stats.melee_dmg += level / 2;
+        }
+
+        let bows = self.level(Skill::Bows) as i32;
+        stats.ranged_acc += bows;
+        stats.ranged_dmg += bows / 2;
+
+        stats.base.ev += self.level(Skill::Dodging) as i32;
+        stats.base.ac += self.level(Skill::Armor) as i32 / 2;
+    }
+}
+
+/// Skills of a freshly created character; monsters never improve them
+pub fn starting(race: Race) -> Skills {
+    match race {
+        Race::Human => Skills::new(&[(Skill::Blades, 1), (Skill::Armor, 1), (Skill::Dodging, 1)]),
+        Race::Elf => Skills::new(&[(Skill::Bows, 2), (Skill::Dodging, 1), (Skill::Stealth, 1)]),
+        Race::Dwarf => Skills::new(&[(Skill::Axes, 2), (Skill::Armor, 1), (Skill::Digging, 2)]),
+        Race::Rat => Skills::new(&[(Skill::Dodging, 2), (Skill::Stealth, 2)]),
+        Race::Goblin => Skills::new(&[(Skill::Blades, 1), (Skill::Bows, 1), (Skill::Stealth, 1)]),
+        Race::Troll => Skills::new(&[(Skill::Armor, 2)]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use game::actor::Stats;
+
+    fn stats() -> EffectiveStats {
+        EffectiveStats {
+            base: Stats::new(Race::Human),
+            melee_dmg: 0,
+            melee_acc: 0,
+            melee_str_req: 0,
+            ranged_dmg: 0,
+            ranged_acc: 0,
+            ranged_range: 0,
+            light_emision: 0,
+        }
+    }
+
+    #[test]
+    fn practice_makes_perfect() {
+        let mut skills = Skills::default();
+
+        for _ in 1..SKILL_PRACTICE {
+            assert_eq!(skills.practise(Skill::Polearms), None);
+        }
+        assert_eq!(skills.practise(Skill::Polearms), Some(1));
+        assert_eq!(skills.level(Skill::Polearms), 1);
+        assert_eq!(skills.level(Skill::Blades), 0);
+    }
+
+    #[test]
+    fn only_the_wielded_weapon_skill_applies() {
+        let skills = Skills::new(&[(Skill::Polearms, 4), (Skill::Blades, 2)]);
+
+        let mut with_spear = stats();
+        skills.apply(&mut with_spear, Some(Skill::Polearms));
+        assert_eq!((with_spear.melee_acc, with_spear.melee_dmg), (4, 2));
+
+        let mut bare_handed = stats();
+        skills.apply(&mut bare_handed, None);
+        assert_eq!((bare_handed.melee_acc, bare_handed.melee_dmg), (0, 0));
+    }
+}
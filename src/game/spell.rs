@@ -0,0 +1,85 @@
+//! Spells paid for with MP
+
+use hex2d::{Angle, Coordinate, Direction, Position};
+
+use super::actor::Race;
+use super::consts::*;
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Serialize, Deserialize)]
+pub enum Spell {
+    /// Burns everyone in a cone in front of the caster
+    Flame,
+    /// Hits and stuns everyone around the caster
+    Shockwave,
+    /// Hits the first creature in the facing direction
+    Bolt,
+    /// Teleports the caster to a visible hex nearby
+    Blink,
+}
+
+impl Spell {
+    pub fn description(&self) -> &'static str {
+        match *self {
+            Spell::Flame => "flame",
+            Spell::Shockwave => "shockwave",
+            Spell::Bolt => "bolt",
+            Spell::Blink => "blink",
+        }
+    }
+
+    pub fn mp_cost(&self) -> i32 {
+        match *self {
+            Spell::Flame => 5,
+            Spell::Shockwave => 6,
+            Spell::Bolt => 4,
+            Spell::Blink => 3,
+        }
+    }
+
+    /// Damage before adding the caster's roll
+    pub fn base_dmg(&self) -> i32 {
+        match *self {
+            Spell::Flame => 2,
+            Spell::Shockwave => 1,
+            Spell::Bolt => 4,
+            Spell::Blink => 0,
+        }
+    }
+
+    /// Does casting require picking a hex?
+    pub fn needs_target(&self) -> bool {
+        *self == Spell::Blink
+    }
+
+    /// Hexes affected when cast from `pos`, nearest first
+    ///
+    /// `Blink` affects only its target, so the area is empty.
+    pub fn area(&self, pos: Position) -> Vec<Coordinate> {
+        match *self {
+            Spell::Flame => {
+                let head = pos.coord + pos.dir;
+                vec![head,
+                     head + pos.dir,
+                     head + (pos.dir + Angle::Left),
+                     head + (pos.dir + Angle::Right)]
+            }
+            Spell::Shockwave => Direction::all().iter().map(|&dir| pos.coord + dir).collect(),
+            Spell::Bolt => {
+                (1..BOLT_RANGE + 1)
+                    .map(|i| pos.coord + Coordinate::from(pos.dir).scale(i))
+                    .collect()
+            }
+            Spell::Blink => vec![],
+        }
+    }
+}
+
+/// Spells known by a freshly created character
+pub fn starting(race: Race) -> Vec<Spell> {
+    match race {
+        Race::Elf => vec![Spell::Flame, Spell::Bolt, Spell::Blink, Spell::Shockwave],
+        Race::Human => vec![Spell::Bolt, Spell::Blink],
+        Race::Dwarf => vec![Spell::Shockwave],
+        Race::Rat | Race::Goblin | Race::Troll => vec![],
+    }
+}
@@ -0,0 +1,197 @@
+//! Temporary conditions of actors
+
+use std::cmp;
+use std::collections::BTreeMap;
+
+use super::actor::EffectiveStats;
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
+pub enum Status {
+    /// Loses `strength` HP every own tick; doesn't get worse, only longer
+    Poisoned,
+    /// Loses `strength` HP every own tick; every new wound adds to it
+    Bleeding,
+    /// Can't act
+    Stunned,
+    Slowed,
+    Hasted,
+    /// Sees only the adjacent tiles
+    Blinded,
+    /// Stumbles in random directions
+    Confused,
+}
+
+impl Status {
+    pub fn description(&self) -> &'static str {
+        match *self {
+            Status::Poisoned => "poisoned",
+            Status::Bleeding => "bleeding",
+            Status::Stunned => "stunned",
+            Status::Slowed => "slowed",
+            Status::Hasted => "hasted",
+            Status::Blinded => "blinded",
+            Status::Confused => "confused",
+        }
+    }
+
+    /// Applying the status again adds to its strength
+    ///
+    /// Other statuses only get their duration refreshed.
+    pub fn stacks(&self) -> bool {
+        match *self {
+            Status::Bleeding => true,
+            _ => false,
+        }
+    }
+
+    /// Status removed when this one is applied
+    pub fn cancels(&self) -> Option<Status> {
+        match *self {
+            Status::Slowed => Some(Status::Hasted),
+            Status::Hasted => Some(Status::Slowed),
+            _ => None,
+        }
+    }
+
+    /// Is it good to have?
+    pub fn is_beneficial(&self) -> bool {
+        *self == Status::Hasted
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Effect {
+    /// Own ticks left
+    pub duration: u32,
+    pub strength: i32,
+}
+
+/// Statuses an actor is under, in a stable order
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Statuses {
+    effects: BTreeMap<Status, Effect>,
+}
+
+impl Statuses {
+    pub fn has(&self, status: Status) -> bool {
+        self.effects.contains_key(&status)
+    }
+
+    pub fn get(&self, status: Status) -> Option<&Effect> {
+        self.effects.get(&status)
+    }
+
+    pub fn iter(&self) -> ::std::collections::btree_map::Iter<Status, Effect> {
+        self.effects.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.effects.is_empty()
+    }
+
+    /// Apply `status`, following the stacking rules
+    ///
+    /// Returns the status it has cancelled, if any.
+    pub fn add(&mut self, status: Status, duration: u32, strength: i32) -> Option<Status> {
+        let cancelled = status.cancels().and_then(|c| self.effects.remove(&c).map(|_| c));
+
+        let effect = self.effects.entry(status).or_insert(Effect {
+            duration: 0,
+            strength: 0,
+        });
+        effect.duration = cmp::max(effect.duration, duration);
+        effect.strength = if status.stacks() {
+            effect.strength + strength
+        } else {
+            cmp::max(effect.strength, strength)
+        };
+
+        cancelled
+    }
+
+    pub fn remove(&mut self, status: Status) -> bool {
+        self.effects.remove(&status).is_some()
+    }
+
+    /// HP lost to the statuses in one own tick
+    pub fn damage(&self) -> i32 {
+        self.effects
+            .iter()
+            .map(|(&status, effect)| {
+                match status {
+                    Status::Poisoned | Status::Bleeding => effect.strength,
+                    _ => 0,
+                }
+            })
+            .sum()
+    }
+
+    /// Count down the durations
+    ///
+    /// Returns the statuses that have worn off.
+    pub fn tick(&mut self) -> Vec<Status> {
+        for effect in self.effects.values_mut() {
+            effect.duration = effect.duration.saturating_sub(1);
+        }
+
+        let ended: Vec<_> = self.effects
+                                .iter()
+                                .filter(|&(_, effect)| effect.duration == 0)
+                                .map(|(&status, _)| status)
+                                .collect();
+        for status in &ended {
+            self.effects.remove(status);
+        }
+        ended
+    }
+
+    /// Modify already calculated stats
+    pub fn apply(&self, stats: &mut EffectiveStats) {
+        for &status in self.effects.keys() {
+            match status {
+                Status::Slowed => stats.base.speed = stats.base.speed * 2 / 3,
+                Status::Hasted => stats.base.speed = stats.base.speed * 3 / 2,
+                Status::Blinded => {
+                    stats.base.vision = cmp::min(stats.base.vision, 1);
+                    stats.base.infravision = 0;
+                }
+                Status::Stunned => stats.base.ev /= 2,
+                Status::Confused => {
+                    stats.melee_acc /= 2;
+                    stats.ranged_acc /= 2;
+                }
+                Status::Poisoned | Status::Bleeding => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn poison_is_refreshed() {
+        let mut statuses = Statuses::default();
+        statuses.add(Status::Poisoned, 6, 1);
+        statuses.tick();
+        statuses.add(Status::Poisoned, 6, 1);
+
+        assert_eq!(statuses.get(Status::Poisoned),
+                   Some(&Effect {
+                       duration: 6,
+                       strength: 1,
+                   }));
+        assert_eq!(statuses.damage(), 1);
+    }
+
+    #[test]
+    fn bleeding_stacks() {
+        let mut statuses = Statuses::default();
+        statuses.add(Status::Bleeding, 4, 1);
+        statuses.add(Status::Bleeding, 4, 1);
+        statuses.add(Status::Poisoned, 6, 1);
+
+        assert_eq!(statuses.damage(), 3);
+    }
+}
@@ -4,9 +4,11 @@ pub use self::Feature::*;
 pub use super::area;
 
 use std::fmt;
-use rand::{Rng, self};
+use rand::Rng;
 
-#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+use game;
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, Serialize, Deserialize)]
 pub enum Type {
     Wall,
     Empty,
@@ -31,10 +33,11 @@ impl fmt::Display for Type {
 }
 
 
-#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, Serialize, Deserialize)]
 pub enum Feature {
     Door(bool),
     Stairs,
+    StairsUp,
     Statue,
 }
 
@@ -44,6 +47,7 @@ impl Feature {
             Door(true) => "open door",
             Door(false) => "closed door",
             Stairs => "stairs down",
+            StairsUp => "stairs up",
             Statue => "statue",
         }
     }
@@ -56,7 +60,7 @@ impl fmt::Display for Feature {
     }
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
 pub struct Tile {
     pub type_: Type,
     pub feature: Option<Feature>,
@@ -128,11 +132,17 @@ impl Tile {
         self.type_.can_dig_through()
     }
 
-    pub fn dig(&mut self) {
-        let r = rand::thread_rng().gen_range(0..10);
+    /// Try to break through; every level of the digging skill improves the odds
+    ///
+    /// Returns whether it broke.
+    pub fn dig(&mut self, skill: u32, rng: &mut game::Rng) -> bool {
+        let r = rng.gen_range(0..10);
         match self.type_ {
-            Wall => if r < 5 { self.type_ = Empty },
-            _ => {}
+            Wall if r < 5 + skill => {
+                self.type_ = Empty;
+                true
+            }
+            _ => false,
         }
     }
 }
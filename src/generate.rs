@@ -1,8 +1,6 @@
 
-use rand;
 use rand::{Rng, SeedableRng};
-use rand::rngs::SmallRng;
-use std::collections::{HashMap, VecDeque};
+use std::collections::{BTreeSet, HashMap, VecDeque};
 use std::hash::BuildHasherDefault;
 use fnv::FnvHasher;
 
@@ -10,9 +8,11 @@ use hex2d as h2d;
 use hex2d::Angle::*;
 use hex2d::{Direction, Position, Coordinate};
 use game::tile;
-use game::{Map, Actors, Items};
+use game::{self, Map, Actors, Items};
 use game::area;
 use game::item;
+use game::artifact::Artifact;
+use game::consts::FINAL_LEVEL;
 use game::actor::{Race, Actor};
 
 type EndpointQueue = VecDeque<h2d::Position>;
@@ -26,11 +26,13 @@ pub struct DungeonGenerator {
     endpoints: EndpointQueue,
     actors: Actors,
     items: Items,
-    rng: SmallRng
+    /// Already in the game, so not to be generated again
+    artifacts: BTreeSet<Artifact>,
+    rng: game::Rng,
 }
 
 impl DungeonGenerator {
-    pub fn new(level: u32) -> DungeonGenerator {
+    pub fn new(level: u32, seed: u64, artifacts: BTreeSet<Artifact>) -> DungeonGenerator {
         DungeonGenerator {
             level: level,
             start: None,
@@ -40,7 +42,8 @@ impl DungeonGenerator {
             endpoints: VecDeque::new(),
             actors: Default::default(),
             items: Default::default(),
-            rng: SmallRng::from_seed(rand::thread_rng().gen::<[u8; 32]>()),
+            artifacts: artifacts,
+            rng: game::Rng::seed_from_u64(seed),
         }
     }
 }
@@ -153,10 +156,16 @@ impl DungeonGenerator {
             match self.rng.gen_range(0..6) {
                 2 => {
                     if self.stairs.is_none() {
-                        self.map.insert(coord,
-                                        *tile::Tile::new(tile::Empty)
-                                             .add_feature(tile::Stairs)
-                                             .add_area(area));
+                        if self.level == FINAL_LEVEL {
+                            // the orb waits where the stairs would be
+                            self.map.insert(coord, *tile::Tile::new(tile::Empty).add_area(area));
+                            self.items.insert(coord, vec![Box::new(item::Item::new(item::Type::Orb, vec![]))]);
+                        } else {
+                            self.map.insert(coord,
+                                            *tile::Tile::new(tile::Empty)
+                                                 .add_feature(tile::Stairs)
+                                                 .add_area(area));
+                        }
                         self.stairs = Some(coord);
                         self.tile_count += 1;
                     }
@@ -219,7 +228,8 @@ impl DungeonGenerator {
         }
 
         if self.rng.gen_bool(1.0 / 2.0) {
-            self.items.insert(coord, item::random(self.level as i32));
+            let item = item::random(&mut self.rng, self.level as i32, &mut self.artifacts);
+            self.items.entry(coord).or_insert_with(|| vec![item]);
         }
     }
 
@@ -236,6 +246,9 @@ impl DungeonGenerator {
 
         self.generate_room_inplace(start_pos, first_room_r);
 
+        // on the first level these lead out of the dungeon
+        self.map.get_mut(&start).unwrap().add_feature(tile::StairsUp);
+
         self.endpoint_push(start_pos);
 
         while self.tile_count < size || self.stairs.is_none() {
@@ -290,6 +303,13 @@ impl DungeonGenerator {
     }
 }
 
-pub fn gen_level(level: u32) -> (Map, Actors, Items) {
-    DungeonGenerator::new(level).generate_map(Coordinate::new(0, 0), 25 + level * 50)
+/// Generate a level of the dungeon: the map with monsters and items in it
+///
+/// The same `level`, `seed` and `artifacts` always give the same result.
+/// Artifacts placed in the level are added to `artifacts`.
+pub fn gen_level(level: u32, seed: u64, artifacts: &mut BTreeSet<Artifact>) -> (Map, Actors, Items) {
+    let (map, actors, items) = DungeonGenerator::new(level, seed, artifacts.clone())
+                                   .generate_map(Coordinate::new(0, 0), 25 + level * 50);
+    artifacts.extend(items.values().flat_map(|pile| pile.iter()).filter_map(|item| item.artifact()));
+    (map, actors, items)
 }
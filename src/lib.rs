@@ -0,0 +1,41 @@
+//! Rules of rhex - hexagonal map roguelike
+//!
+//! Everything here is independent of any user interface. The game is driven
+//! through `game::Engine`, which owns the dungeon levels (`game::Location`)
+//! with all the actors (`game::Actor`) and items (`game::item`) in them.
+//! Player input is given as `game::Action`s, and the monsters are controlled
+//! by implementations of `ai::Ai`.
+//!
+//! ```no_run
+//! extern crate rhex;
+//!
+//! use rhex::game::{Action, Engine};
+//! use rhex::game::actor::Race;
+//!
+//! fn main() {
+//!     let mut engine = Engine::new(1234);
+//!     engine.initial_spawn(Race::Human);
+//!
+//!     engine.advance_until_player_input().unwrap();
+//!     engine.player_act(Action::Wait).unwrap();
+//!     engine.advance_until_player_input().unwrap();
+//! }
+//! ```
+#![cfg_attr(feature="clippy", feature(plugin))]
+#![cfg_attr(feature="clippy", plugin(clippy))]
+
+extern crate simplemap;
+extern crate hex2d;
+extern crate hex2d_dpcext as hex2dext;
+extern crate rand;
+extern crate rand_pcg;
+extern crate fnv;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate bincode;
+
+pub mod ai;
+pub mod game;
+pub mod generate;
+pub mod util;
@@ -1,7 +1,7 @@
 #![cfg_attr(feature="clippy", feature(plugin))]
 #![cfg_attr(feature="clippy", plugin(clippy))]
 
-extern crate simplemap;
+extern crate rhex;
 extern crate ncurses;
 extern crate hex2d;
 extern crate hex2d_dpcext as hex2dext;
@@ -11,19 +11,40 @@ extern crate chrono;
 //#[macro_use]
 extern crate log;
 extern crate fern;
-extern crate fnv;
 
-mod ai;
+use rhex::{game, util};
+
 mod curses;
-mod game;
-mod generate;
-mod util;
 mod logging;
+mod options;
 
 fn main() {
+    let opts = match options::Options::from_args() {
+        Ok(opts) => opts,
+        Err(msg) => {
+            println!("{}", msg);
+            std::process::exit(1);
+        }
+    };
+
+    let journal = match opts.replay {
+        Some(ref path) => {
+            match game::Journal::load(path) {
+                Ok(journal) => Some(journal),
+                Err(err) => {
+                    println!("Couldn't load replay {}: {}", path, err);
+                    std::process::exit(1);
+                }
+            }
+        }
+        None => None,
+    };
+
     logging::init();
 
-    let mut ui = match curses::Ui::new() {
+    let seed = opts.seed.unwrap_or_else(rand::random);
+
+    let mut ui = match curses::Ui::new(seed) {
         Ok(res) => res,
         Err(err) => {
             match err {
@@ -34,5 +55,9 @@ fn main() {
         }
     };
 
+    if let Some(journal) = journal {
+        ui.start_replay(journal, opts.replay_delay);
+    }
+
     ui.run();
 }
@@ -0,0 +1,59 @@
+use std::env;
+
+/// Command line options
+#[derive(Clone, Debug)]
+pub struct Options {
+    /// Seed for the game; random if not given
+    pub seed: Option<u64>,
+    /// Journal of a game to replay
+    pub replay: Option<String>,
+    /// Delay between replayed turns in milliseconds
+    pub replay_delay: u64,
+}
+
+const REPLAY_DELAY_DEFAULT: u64 = 100;
+
+pub fn usage() -> String {
+    let mut s = String::new();
+    s.push_str("Usage: rhex [OPTIONS]\n\n");
+    s.push_str("Options:\n");
+    s.push_str("    --seed <N>            start the game with a given seed\n");
+    s.push_str("    --replay <FILE>       replay a game recorded in FILE (eg. rhex.replay)\n");
+    s.push_str("    --replay-delay <MS>   milliseconds between replayed turns\n");
+    s.push_str("    --help                print this message\n");
+    s
+}
+
+impl Options {
+    pub fn from_args() -> Result<Options, String> {
+        let mut opts = Options {
+            seed: None,
+            replay: None,
+            replay_delay: REPLAY_DELAY_DEFAULT,
+        };
+        let mut args = env::args().skip(1);
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--seed" => {
+                    let val = args.next().ok_or("--seed requires a value")?;
+                    let seed = val.parse().map_err(|_| format!("invalid seed: {}", val))?;
+                    opts.seed = Some(seed);
+                }
+                "--replay" => {
+                    let val = args.next().ok_or("--replay requires a file")?;
+                    opts.replay = Some(val);
+                }
+                "--replay-delay" => {
+                    let val = args.next().ok_or("--replay-delay requires a value")?;
+                    let delay = val.parse().map_err(|_| format!("invalid delay: {}", val))?;
+                    opts.replay_delay = delay;
+                }
+                "--help" | "-h" => return Err(usage()),
+                _ => return Err(format!("unknown option: {}\n\n{}", arg, usage())),
+            }
+        }
+
+        Ok(opts)
+    }
+}
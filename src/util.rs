@@ -1,25 +1,27 @@
 use std::cmp;
-use rand::{self, Rng};
+use rand::Rng;
 
 use hex2d::{Position, Direction, Coordinate, Angle};
 
-pub fn random_pos(x: i32, y: i32) -> Position {
+use game;
 
-    let dir = Direction::from_int(rand::thread_rng().gen_range(0..6));
+pub fn random_pos(rng: &mut game::Rng, x: i32, y: i32) -> Position {
+
+    let dir = Direction::from_int(rng.gen_range(0..6));
 
     Position::new(Coordinate::new(x, y), dir)
 }
 
 
 /// Roll a against b
-pub fn roll(a: i32, b: i32) -> bool {
+pub fn roll(rng: &mut game::Rng, a: i32, b: i32) -> bool {
 
     let base = cmp::max(a, b) / 4;
 
     let a = cmp::max(a - base, 1);
     let b = cmp::max(b - base, 1);
 
-    rand::thread_rng().gen_range(0..(a + b)) < a
+    rng.gen_range(0..(a + b)) < a
 }
 
 pub fn circular_move(center: Position, cur: Position, angle: Angle) -> Position {
@@ -0,0 +1,74 @@
+extern crate rhex;
+
+use rhex::game::{tile, Action, Actor, Engine};
+use rhex::game::actor::Race;
+use rhex::game::error::Error;
+use rhex::game::item::{Item, Type};
+
+fn new_game() -> Engine {
+    let mut engine = Engine::new(99);
+    engine.initial_spawn(Race::Human);
+    engine.advance_until_player_input().unwrap();
+    engine
+}
+
+fn player_mut(engine: &mut Engine) -> &mut Actor {
+    let player_id = engine.current_location().player_id();
+    engine.current_location_mut().actors_byid.get_mut(&player_id).unwrap()
+}
+
+#[test]
+fn no_player() {
+    let mut engine = Engine::new(99);
+
+    assert_eq!(engine.start_turn(), Err(Error::NoPlayer));
+    assert_eq!(engine.player_act(Action::Wait), Err(Error::NoPlayer));
+}
+
+#[test]
+fn wrong_phase() {
+    let mut engine = new_game();
+    assert!(engine.needs_player_input());
+
+    assert_eq!(engine.one_actor_tick(), Err(Error::WrongPhase));
+    assert_eq!(engine.start_turn(), Err(Error::WrongPhase));
+    assert_eq!(engine.player_skip_act(), Err(Error::WrongPhase));
+}
+
+#[test]
+fn invalid_action() {
+    let mut engine = new_game();
+    let turn = engine.turn();
+    let recorded = engine.journal().entries.len();
+
+    assert_eq!(engine.player_act(Action::Pick(100)),
+               Err(Error::InvalidAction(Action::Pick(100))));
+
+    // refused actions take no time and aren't recorded
+    assert!(engine.needs_player_input());
+    assert_eq!(engine.turn(), turn);
+    assert_eq!(engine.journal().entries.len(), recorded);
+}
+
+#[test]
+fn player_dead() {
+    let mut engine = new_game();
+    player_mut(&mut engine).hp = 0;
+
+    assert_eq!(engine.advance_until_player_input(), Err(Error::PlayerDead));
+    assert_eq!(engine.player_act(Action::Wait), Err(Error::PlayerDead));
+}
+
+#[test]
+fn game_won() {
+    let mut engine = new_game();
+    let coord = engine.player().coord();
+    engine.current_location_mut().map[coord].feature = Some(tile::Feature::StairsUp);
+    assert!(player_mut(&mut engine).pick_item(Box::new(Item::new(Type::Orb, vec![]))).is_none());
+
+    engine.player_act(Action::Ascend).unwrap();
+
+    assert!(engine.is_won());
+    assert_eq!(engine.advance_until_player_input(), Err(Error::GameWon));
+    assert_eq!(engine.player_act(Action::Wait), Err(Error::GameWon));
+}
@@ -0,0 +1,110 @@
+extern crate bincode;
+extern crate hex2d;
+extern crate rhex;
+
+use std::env;
+use std::fs::File;
+use std::path::PathBuf;
+use std::process;
+
+use hex2d::Angle;
+
+use rhex::game::{save, Action, Engine, Journal};
+use rhex::game::actor::Race;
+use rhex::game::error::Error;
+use rhex::game::journal::Input;
+
+fn temp_path(name: &str) -> PathBuf {
+    env::temp_dir().join(format!("rhex-test-{}-{}", process::id(), name))
+}
+
+/// Play a fixed sequence of actions, skipping the impossible ones
+fn play(engine: &mut Engine, turns: usize) {
+    let actions = [Action::Move(Angle::Forward),
+                   Action::Turn(Angle::Left),
+                   Action::Move(Angle::Forward),
+                   Action::Move(Angle::Forward),
+                   Action::Turn(Angle::Right),
+                   Action::Pick(0),
+                   Action::Wait];
+
+    for &action in actions.iter().cycle().take(turns) {
+        match engine.advance_until_player_input() {
+            Ok(_) => {}
+            Err(Error::PlayerDead) | Err(Error::GameWon) => return,
+            Err(err) => panic!("engine failed: {}", err),
+        }
+
+        match engine.player_act(action) {
+            Ok(()) | Err(Error::InvalidAction(_)) => {}
+            Err(Error::PlayerDead) => return,
+            Err(err) => panic!("engine failed: {}", err),
+        }
+    }
+}
+
+/// Feed the input recorded in a journal to a new game
+fn replay(journal: &Journal) -> Engine {
+    let mut engine = Engine::new(journal.seed);
+    engine.initial_spawn(journal.race.unwrap());
+
+    for entry in &journal.entries {
+        match entry.input {
+            Input::Act(action) => {
+                engine.advance_until_player_input().unwrap();
+                assert_eq!(engine.turn(), entry.turn);
+                engine.player_act(action).unwrap();
+            }
+            Input::Raise(attr) => {
+                engine.advance_until_player_input().unwrap();
+                engine.player_raise_attribute(attr).unwrap();
+            }
+            // done by `advance_until_player_input`
+            Input::Skip => {}
+        }
+    }
+
+    let _ = engine.advance_until_player_input();
+    engine
+}
+
+#[test]
+fn replay_gives_the_same_game() {
+    let mut engine = Engine::new(1234);
+    engine.initial_spawn(Race::Dwarf);
+    play(&mut engine, 200);
+    let _ = engine.advance_until_player_input();
+
+    let path = temp_path("game.replay");
+    engine.journal().save(&path).unwrap();
+    let journal = Journal::load(&path);
+    let _ = save::remove(&path);
+    let journal = journal.unwrap();
+
+    let replayed = replay(&journal);
+
+    assert_eq!(replayed.journal().entries, engine.journal().entries);
+    assert_eq!(replayed.turn(), engine.turn());
+    assert_eq!(replayed.current_location().level,
+               engine.current_location().level);
+    assert_eq!(replayed.player().pos, engine.player().pos);
+    assert_eq!(replayed.player().hp, engine.player().hp);
+    assert_eq!(replayed.player().stats, engine.player().stats);
+    assert_eq!(replayed.record().kills, engine.record().kills);
+}
+
+#[test]
+fn journal_of_another_version_is_rejected() {
+    let path = temp_path("old.replay");
+    {
+        let file = File::create(&path).unwrap();
+        bincode::serialize_into(file, &0u32).unwrap();
+    }
+    let journal = Journal::load(&path);
+    let _ = save::remove(&path);
+
+    match journal {
+        Err(save::Error::Version(0)) => {}
+        other => panic!("unexpected result: {:?}", other.map(|journal| journal.seed)),
+    }
+}
@@ -0,0 +1,62 @@
+extern crate rhex;
+
+use std::env;
+use std::path::PathBuf;
+use std::process;
+
+use rhex::game::{save, Action, Engine};
+use rhex::game::actor::{self, Race};
+
+fn temp_path(name: &str) -> PathBuf {
+    env::temp_dir().join(format!("rhex-test-{}-{}", process::id(), name))
+}
+
+fn wait(engine: &mut Engine) -> Vec<actor::Id> {
+    engine.player_act(Action::Wait).unwrap();
+    engine.advance_until_player_input().unwrap()
+}
+
+#[test]
+fn save_and_load() {
+    let mut engine = Engine::new(42);
+    engine.initial_spawn(Race::Human);
+    engine.advance_until_player_input().unwrap();
+    for _ in 0..5 {
+        wait(&mut engine);
+    }
+
+    let path = temp_path("roundtrip.sav");
+    save::save(&engine, &path).unwrap();
+    let loaded = save::load(&path);
+    let _ = save::remove(&path);
+    let mut loaded = loaded.unwrap();
+
+    assert_eq!(loaded.turn(), engine.turn());
+    assert_eq!(loaded.player().pos, engine.player().pos);
+    assert_eq!(loaded.player().hp, engine.player().hp);
+    assert_eq!(loaded.player().stats, engine.player().stats);
+
+    // the rng and the monsters are restored too, so the game goes on the same
+    for _ in 0..5 {
+        assert_eq!(wait(&mut loaded), wait(&mut engine));
+        assert_eq!(loaded.turn(), engine.turn());
+        assert_eq!(loaded.player().pos, engine.player().pos);
+        assert_eq!(loaded.player().hp, engine.player().hp);
+    }
+}
+
+#[test]
+fn save_replaces_the_old_one() {
+    let mut engine = Engine::new(7);
+    engine.initial_spawn(Race::Elf);
+    engine.advance_until_player_input().unwrap();
+
+    let path = temp_path("replace.sav");
+    save::save(&engine, &path).unwrap();
+    wait(&mut engine);
+    save::save(&engine, &path).unwrap();
+    let loaded = save::load(&path);
+    let _ = save::remove(&path);
+
+    assert_eq!(loaded.unwrap().turn(), engine.turn());
+}